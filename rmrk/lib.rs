@@ -259,6 +259,16 @@ pub mod rmrk_equippable_lazy {
             );
             instance
         }
+
+        /// Burn token owned by the caller
+        #[ink(message)]
+        pub fn burn(&mut self, id: Id) -> Result<(), PSP34Error> {
+            let caller = Self::env().caller();
+            if self._owner_of(&id) != Some(caller) {
+                return Err(PSP34Error::NotApproved)
+            }
+            self._burn_from(caller, id)
+        }
     }
 
     impl psp34::Internal for Rmrk {
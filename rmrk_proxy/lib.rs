@@ -2,7 +2,9 @@
 #![feature(min_specialization)]
 
 pub mod proxy;
+pub mod randomness;
 pub mod types;
 
 pub use proxy::*;
+pub use randomness::*;
 pub use types::*;
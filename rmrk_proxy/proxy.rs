@@ -23,8 +23,24 @@
 mod rmrk_proxy {
     use crate::{
         ensure,
+        randomness,
+        CollectionConfig,
+        CollectionId,
+        GasLimits,
+        MintCommitment,
+        MintVoucher,
+        PricingStrategy,
+        Proposal,
+        ProposedChange,
         ProxyError,
+        RandomnessSource,
         Result,
+        SalePhase,
+        CONFIG_ADMIN,
+        MINTER,
+        PAUSER,
+        PRICE_ADMIN,
+        TREASURER,
     };
     use ink::{
         env::{
@@ -40,13 +56,158 @@ mod rmrk_proxy {
     };
     use openbrush::{
         contracts::{
+            access_control::*,
             ownable::*,
-            psp34::Id,
+            pausable::*,
+            psp22::PSP22Error,
+            psp34::{
+                Id,
+                PSP34Error,
+            },
             reentrancy_guard::*,
         },
         modifiers,
-        traits::Storage,
+        traits::{
+            Storage,
+            String,
+        },
     };
+    use rmrk::{
+        errors::Error as RmrkError,
+        types::AssetId,
+    };
+
+    const DEFAULT_GAS_LIMIT: u64 = 5_000_000_000;
+    /// Version of the storage layout written by this code, bumped whenever `migrate` has to
+    /// transform storage left by a previous version.
+    const STORAGE_VERSION: u32 = 2;
+    /// Time after which a proposed change of a critical setting can be executed, two days.
+    const DEFAULT_TIMELOCK_DELAY: Timestamp = 2 * 24 * 60 * 60 * 1000;
+    const DEFAULT_MAX_MINTS_PER_CALL: u32 = 10;
+    /// Collection registered by the constructor and used by all single collection messages.
+    const DEFAULT_COLLECTION: CollectionId = 0;
+    const DEFAULT_REVEAL_WINDOW: BlockNumber = 256;
+    /// Share of an expired commitment's deposit kept by the proxy, in basis points.
+    const DEFAULT_COMMITMENT_PENALTY: u16 = 10_000;
+    /// Payee shares are expressed in basis points.
+    const MAX_BASIS_POINTS: Balance = 10_000;
+    /// Odds returned by `asset_odds` are expressed in parts per billion.
+    const ODDS_DENOMINATOR: u64 = 1_000_000_000;
+
+    /// Event emitted when a token is minted through the proxy.
+    #[ink(event)]
+    pub struct Minted {
+        #[ink(topic)]
+        payer: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        #[ink(topic)]
+        token_id: Id,
+        asset_id: AssetId,
+        price: Balance,
+        refund: Balance,
+        payment_token: Option<AccountId>,
+    }
+
+    /// Event emitted when minting is paused.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when minting is unpaused.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the mint price of a collection is changed.
+    #[ink(event)]
+    pub struct MintPriceChanged {
+        #[ink(topic)]
+        collection_id: CollectionId,
+        old_price: Balance,
+        new_price: Balance,
+    }
+
+    /// Event emitted when the RMRK contract address is changed.
+    #[ink(event)]
+    pub struct RmrkContractChanged {
+        old_address: Option<AccountId>,
+        #[ink(topic)]
+        new_address: AccountId,
+    }
+
+    /// Event emitted when the catalog contract address is changed.
+    #[ink(event)]
+    pub struct CatalogContractChanged {
+        old_address: Option<AccountId>,
+        #[ink(topic)]
+        new_address: AccountId,
+    }
+
+    /// Event emitted when a collection is registered.
+    #[ink(event)]
+    pub struct CollectionRegistered {
+        #[ink(topic)]
+        collection_id: CollectionId,
+        #[ink(topic)]
+        rmrk_contract: AccountId,
+    }
+
+    /// Event emitted when a collection is retired.
+    #[ink(event)]
+    pub struct CollectionRetired {
+        #[ink(topic)]
+        collection_id: CollectionId,
+    }
+
+    /// Event emitted when minting of a collection is paused or unpaused.
+    #[ink(event)]
+    pub struct CollectionPausedChanged {
+        #[ink(topic)]
+        collection_id: CollectionId,
+        paused: bool,
+    }
+
+    /// Event emitted when a change of a critical setting is proposed.
+    #[ink(event)]
+    pub struct ProposalCreated {
+        change: ProposedChange,
+        executable_at: Timestamp,
+    }
+
+    /// Event emitted when a proposed change is executed.
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        change: ProposedChange,
+    }
+
+    /// Event emitted when a proposed change is cancelled.
+    #[ink(event)]
+    pub struct ProposalCancelled {
+        change: ProposedChange,
+    }
+
+    /// Event emitted when the owner proposes a new owner.
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    /// Event emitted when the proposed owner accepts the ownership.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
 
     // Proxy contract storage
     #[ink(storage)]
@@ -55,8 +216,12 @@ mod rmrk_proxy {
         #[storage_field]
         ownable: ownable::Data,
         #[storage_field]
+        access: access_control::Data,
+        #[storage_field]
         guard: reentrancy_guard::Data,
         #[storage_field]
+        pause: pausable::Data,
+        #[storage_field]
         proxy: crate::types::Data,
     }
 
@@ -68,277 +233,4366 @@ mod rmrk_proxy {
             mint_price: Balance,
         ) -> Self {
             let mut instance = Self::default();
-            instance.proxy.rmrk_contract = Option::Some(rmrk_contract);
-            instance.proxy.catalog_contract = Option::Some(catalog_contract);
+            instance.proxy.collections.insert(
+                &DEFAULT_COLLECTION,
+                &CollectionConfig {
+                    rmrk_contract,
+                    catalog_contract,
+                    mint_price,
+                    asset_pool: Vec::new(),
+                    paused: false,
+                },
+            );
+            instance
+                .proxy
+                .next_collection_id
+                .set(&(DEFAULT_COLLECTION + 1));
             instance.proxy.salt = 0;
-            instance.proxy.mint_price = mint_price;
+            instance.proxy.max_mints_per_call = DEFAULT_MAX_MINTS_PER_CALL;
+            instance.proxy.reveal_window = DEFAULT_REVEAL_WINDOW;
+            instance.proxy.version = STORAGE_VERSION;
+            instance.proxy.timelock_delay.set(&DEFAULT_TIMELOCK_DELAY);
+            instance.proxy.gas_limits = GasLimits {
+                query: DEFAULT_GAS_LIMIT,
+                mint: DEFAULT_GAS_LIMIT,
+                add_asset_to_token: DEFAULT_GAS_LIMIT,
+                transfer: DEFAULT_GAS_LIMIT,
+            };
 
             let caller = instance.env().caller();
             instance._init_with_owner(caller);
+            instance._init_with_admin(caller);
+            for role in [PRICE_ADMIN, CONFIG_ADMIN, PAUSER, TREASURER, MINTER] {
+                instance._setup_role(role, caller);
+            }
             instance
         }
 
         #[ink(message, payable)]
-        #[modifiers(non_reentrant)]
+        #[modifiers(when_not_paused, non_reentrant)]
         pub fn mint(&mut self) -> Result<()> {
-            const GAS_LIMIT: u64 = 5_000_000_000;
-            const MAX_ASSETS: u32 = 255;
+            let caller = Self::env().caller();
+            self.mint_from(DEFAULT_COLLECTION, caller)
+        }
 
-            let transferred_value = Self::env().transferred_value();
+        /// Mints a token of a registered collection to the caller. Sale phases, pricing
+        /// strategies, asset weights and wallet limits apply only to the default collection,
+        /// other collections are sold at their own fixed price.
+        #[ink(message, payable)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn mint_collection(&mut self, collection_id: CollectionId) -> Result<()> {
+            let caller = Self::env().caller();
+            self.mint_from(collection_id, caller)
+        }
+
+        /// Mints a token the same way as `mint`, but transfers it to `recipient`.
+        /// The caller pays for the mint.
+        #[ink(message, payable)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn mint_to(&mut self, recipient: AccountId) -> Result<()> {
+            self.mint_from(DEFAULT_COLLECTION, recipient)
+        }
+
+        /// Mints a token for an allowlisted caller. `proof` proves that the leaf
+        /// `keccak256(caller ++ max_allowed)`, with `max_allowed` as big endian bytes,
+        /// is part of the allowlist Merkle tree. Pairs of nodes are sorted before hashing.
+        #[ink(message, payable)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn allowlist_mint(&mut self, proof: Vec<[u8; 32]>, max_allowed: u32) -> Result<()> {
+            let collection = self.active_collection(DEFAULT_COLLECTION)?;
+            let caller = Self::env().caller();
+            self.record_wallet_mints(caller, 1)?;
+            let price = self.sale_price(caller, 1, true)?;
+            let refund = self.check_payment(price)?;
+            let root = self
+                .proxy
+                .allowlist_root
+                .ok_or(ProxyError::AllowlistNotSet)?;
             ensure!(
-                transferred_value == self.proxy.mint_price,
-                ProxyError::BadMintValue
-            );
-
-            let total_assets = build_call::<DefaultEnvironment>()
-                .call(self.proxy.rmrk_contract.unwrap())
-                .gas_limit(GAS_LIMIT)
-                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
-                    "MultiAsset::total_assets"
-                ))))
-                .returns::<u32>()
-                .try_invoke()
-                .unwrap();
-            ensure!(total_assets.unwrap() > 0, ProxyError::NoAssetsDefined);
-            // This is temporary since current pseudo random generator is not working with big numbers.
+                Self::verify_merkle_proof(&proof, root, Self::allowlist_leaf(&caller, max_allowed)),
+                ProxyError::NotAllowlisted
+            );
+            let claimed = self.allowlist_claimed(caller);
+            ensure!(claimed < max_allowed, ProxyError::AllowlistLimitReached);
+            self.proxy.allowlist_claimed.insert(&caller, &(claimed + 1));
+
+            let asset_id = self.pick_asset(DEFAULT_COLLECTION, &collection, None)?;
+            self.mint_token(DEFAULT_COLLECTION, caller, price, asset_id, refund, None)?;
+            self.refund(refund)
+        }
+
+        /// Mints a token to the voucher recipient at the voucher price.
+        /// The voucher has to be signed by the registered voucher signer, it can be used only
+        /// once and only until its expiry. The signed message is Keccak256 of the SCALE encoded
+        /// voucher followed by the proxy contract address.
+        #[ink(message, payable)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn mint_with_voucher(
+            &mut self,
+            voucher: MintVoucher,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            let collection = self.active_collection(DEFAULT_COLLECTION)?;
+            let refund = self.check_payment(voucher.price)?;
+            self.redeem_voucher(&voucher, &signature)?;
+            self.record_wallet_mints(voucher.recipient, 1)?;
+
+            let asset_id = self.pick_asset(DEFAULT_COLLECTION, &collection, None)?;
+            self.mint_token(
+                DEFAULT_COLLECTION,
+                voucher.recipient,
+                voucher.price,
+                asset_id,
+                refund,
+                None,
+            )?;
+            self.refund(refund)
+        }
+
+        /// Mints `count` tokens in a single call, each of them with its own random asset.
+        /// Either all tokens are minted or the whole call is reverted.
+        #[ink(message, payable)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn mint_many(&mut self, count: u32) -> Result<()> {
+            let collection = self.active_collection(DEFAULT_COLLECTION)?;
+            ensure!(
+                count > 0 && count <= self.proxy.max_mints_per_call,
+                ProxyError::BadMintCount
+            );
+            let caller = Self::env().caller();
+            self.record_wallet_mints(caller, count)?;
+            let total_price = self.sale_price(caller, count, false)?;
+            let refund = self.check_payment(total_price)?;
+
+            for index in 0..count {
+                let asset_id = self.pick_asset(DEFAULT_COLLECTION, &collection, None)?;
+                // The refund is reported with the last minted token.
+                let token_refund = if index + 1 == count { refund } else { 0 };
+                // Number of minted tokens grows with each mint, so the current price is the price
+                // of the next token.
+                let price = self.current_price();
+                self.mint_token(
+                    DEFAULT_COLLECTION,
+                    caller,
+                    price,
+                    asset_id,
+                    token_refund,
+                    None,
+                )?;
+            }
+
+            self.refund(refund)
+        }
+
+        /// Mints a token paid for with `amount` of an accepted PSP22 token. The caller has to
+        /// approve the proxy to spend `amount`, which has to match the token price.
+        /// No native value is forwarded to the RMRK contract.
+        #[ink(message)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn mint_with_psp22(&mut self, token: AccountId, amount: Balance) -> Result<()> {
+            let collection = self.active_collection(DEFAULT_COLLECTION)?;
+            let price = self
+                .psp22_price(token)
+                .ok_or(ProxyError::Psp22NotAccepted)?;
+            ensure!(amount == price, ProxyError::BadMintValue);
+            let caller = Self::env().caller();
+            self.record_wallet_mints(caller, 1)?;
+            self.sale_price(caller, 1, false)?;
+
+            let selector = ink::selector_bytes!("PSP22::transfer_from");
+            Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(token)
+                    .gas_limit(self.proxy.gas_limits.transfer)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(selector))
+                            .push_arg(caller)
+                            .push_arg(Self::env().account_id())
+                            .push_arg(amount)
+                            .push_arg(Vec::<u8>::new()),
+                    )
+                    .returns::<core::result::Result<(), PSP22Error>>()
+                    .try_invoke(),
+            )?
+            .map_err(|_| ProxyError::Psp22TransferFailed)?;
+
+            let asset_id = self.pick_asset(DEFAULT_COLLECTION, &collection, None)?;
+            self.mint_token(DEFAULT_COLLECTION, caller, amount, asset_id, 0, Some(token))
+        }
+
+        /// Mints a free token with a random asset to each of the recipients.
+        /// The RMRK contract has to be configured with zero mint price.
+        #[ink(message)]
+        #[modifiers(when_not_paused, only_role(MINTER), non_reentrant)]
+        pub fn airdrop(&mut self, recipients: Vec<AccountId>) -> Result<()> {
+            let collection = self.active_collection(DEFAULT_COLLECTION)?;
+            ensure!(
+                !recipients.is_empty()
+                    && recipients.len() <= self.proxy.max_mints_per_call as usize,
+                ProxyError::BadMintCount
+            );
+
+            for recipient in recipients {
+                let asset_id = self.pick_asset(DEFAULT_COLLECTION, &collection, None)?;
+                self.mint_token(DEFAULT_COLLECTION, recipient, 0, asset_id, 0, None)?;
+            }
+            Ok(())
+        }
+
+        /// Pays for a mint and commits to `hash`, which is Keccak256 of the caller's account id
+        /// followed by a secret. The token is minted later by `reveal_mint`.
+        /// Requires `RandomnessSource::ChainExtension`, which provides the block hash used to
+        /// pick the asset.
+        #[ink(message, payable)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn commit_mint(&mut self, hash: [u8; 32]) -> Result<()> {
+            self.active_collection(DEFAULT_COLLECTION)?;
+            let caller = Self::env().caller();
+            self.record_wallet_mints(caller, 1)?;
+            let price = self.sale_price(caller, 1, false)?;
+            let refund = self.check_payment(price)?;
+            ensure!(
+                self.proxy.randomness_source == RandomnessSource::ChainExtension,
+                ProxyError::ChainRandomnessRequired
+            );
+            ensure!(
+                self.proxy.mint_commitments.get(&caller).is_none(),
+                ProxyError::CommitmentExists
+            );
+
+            self.proxy.mint_commitments.insert(
+                &caller,
+                &MintCommitment {
+                    hash,
+                    block_number: Self::env().block_number(),
+                    deposit: price,
+                    phase_start: self.current_phase().map(|phase| phase.start),
+                },
+            );
+            self.refund(refund)
+        }
+
+        /// Reveals the secret of the caller's commitment and mints the token.
+        /// Has to be called after the block following the commit block is sealed, before the
+        /// commitment expires. The asset is picked using the secret and the hash of the block
+        /// following the commit block, so it is unknown at commit time and doesn't depend on
+        /// the block in which the reveal lands.
+        #[ink(message)]
+        #[modifiers(when_not_paused, non_reentrant)]
+        pub fn reveal_mint(&mut self, secret: [u8; 32]) -> Result<()> {
+            let collection = self.active_collection(DEFAULT_COLLECTION)?;
+            let caller = Self::env().caller();
+            let commitment = self
+                .proxy
+                .mint_commitments
+                .get(&caller)
+                .ok_or(ProxyError::NoCommitment)?;
+            let block_number = Self::env().block_number();
+            ensure!(
+                block_number > commitment.block_number.saturating_add(1),
+                ProxyError::RevealTooEarly
+            );
+            ensure!(
+                !self.is_commitment_expired(&commitment),
+                ProxyError::CommitmentExpired
+            );
+            ensure!(
+                Self::commitment_hash(&caller, &secret) == commitment.hash,
+                ProxyError::InvalidSecret
+            );
+            let seed = Self::reveal_seed(&commitment, &secret)?;
+            self.proxy.mint_commitments.remove(&caller);
+
+            let asset_id = self.pick_asset(DEFAULT_COLLECTION, &collection, Some(seed))?;
+            self.mint_token(
+                DEFAULT_COLLECTION,
+                caller,
+                commitment.deposit,
+                asset_id,
+                0,
+                None,
+            )
+        }
+
+        /// Returns the deposit of an expired commitment to the caller, less `commitment_penalty`
+        /// basis points added to the revenue. The mint no longer counts towards the wallet and
+        /// sale phase limits. The penalty keeps committers from skipping the reveal of an asset,
+        /// which anyone can compute once the block after the commit is sealed, and committing
+        /// again for free.
+        #[ink(message)]
+        #[modifiers(non_reentrant)]
+        pub fn refund_commitment(&mut self) -> Result<()> {
+            let caller = Self::env().caller();
+            let commitment = self
+                .proxy
+                .mint_commitments
+                .get(&caller)
+                .ok_or(ProxyError::NoCommitment)?;
+            ensure!(
+                self.is_commitment_expired(&commitment),
+                ProxyError::CommitmentNotExpired
+            );
+
+            self.proxy.mint_commitments.remove(&caller);
+            self.release_commitment_mint(caller, commitment.phase_start);
+            let penalty = commitment
+                .deposit
+                .saturating_mul(self.commitment_penalty() as Balance)
+                / MAX_BASIS_POINTS;
+            self.proxy.revenue = self.proxy.revenue.saturating_add(penalty);
+            let refund = commitment.deposit - penalty;
+            if refund > 0 {
+                Self::env()
+                    .transfer(caller, refund)
+                    .map_err(|_| ProxyError::TransferFailed)?;
+            }
+            Ok(())
+        }
+
+        /// Transfers the share of the revenue not yet released to the payee.
+        #[ink(message)]
+        #[modifiers(non_reentrant)]
+        pub fn release(&mut self, payee: AccountId) -> Result<()> {
+            let pending = self.pending(payee)?;
+            ensure!(pending > 0, ProxyError::NothingToRelease);
+
+            let released = self.released(payee).saturating_add(pending);
+            self.proxy.released.insert(&payee, &released);
+            Self::env()
+                .transfer(payee, pending)
+                .map_err(|_| ProxyError::TransferFailed)
+        }
+
+        /// Transfers `amount` of a PSP22 token collected by `mint_with_psp22` to the owner.
+        #[ink(message)]
+        #[modifiers(only_role(TREASURER))]
+        pub fn withdraw_psp22(&mut self, token: AccountId, amount: Balance) -> Result<()> {
+            let selector = ink::selector_bytes!("PSP22::transfer");
+            Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(token)
+                    .gas_limit(self.proxy.gas_limits.transfer)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(selector))
+                            .push_arg(Self::env().caller())
+                            .push_arg(amount)
+                            .push_arg(Vec::<u8>::new()),
+                    )
+                    .returns::<core::result::Result<(), PSP22Error>>()
+                    .try_invoke(),
+            )?
+            .map_err(|_| ProxyError::Psp22TransferFailed)
+        }
+
+        /// Grants the role to the account. The caller has to have the admin role of `role`.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleType, account: AccountId) -> Result<()> {
+            AccessControl::grant_role(self, role, account).map_err(ProxyError::AccessControlError)
+        }
+
+        /// Revokes the role from the account. The caller has to have the admin role of `role`.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: RoleType, account: AccountId) -> Result<()> {
+            AccessControl::revoke_role(self, role, account).map_err(ProxyError::AccessControlError)
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleType, account: AccountId) -> bool {
+            AccessControl::has_role(self, role, account)
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            Ownable::owner(self)
+        }
+
+        #[ink(message)]
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.proxy.pending_owner.get_or_default()
+        }
+
+        /// Proposes a new owner, who becomes the owner by calling `accept_ownership`.
+        /// The default admin role and the operational roles held by the current owner are
+        /// transferred with the ownership.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()> {
+            self.proxy.pending_owner.set(&Some(new_owner));
+            Self::env().emit_event(OwnershipTransferStarted {
+                previous_owner: self.owner(),
+                new_owner,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            let caller = Self::env().caller();
+            ensure!(
+                self.pending_owner() == Some(caller),
+                ProxyError::NotPendingOwner
+            );
+            let previous_owner = self.owner();
+            self.ownable.owner = caller;
+            self.proxy.pending_owner.set(&None);
+            self.move_roles(previous_owner, caller);
+            Self::env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: caller,
+            });
+            Ok(())
+        }
+
+        /// Proposes replacing the contract code, keeping its storage and address. The code is
+        /// replaced by `execute_proposal` once the timelock delay has passed.
+        /// `migrate` has to be called with the new code if it changes the storage version.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn propose_upgrade(&mut self, code_hash: [u8; 32]) -> Result<()> {
+            self.propose(ProposedChange::CodeHash(code_hash))
+        }
+
+        /// Transforms storage written by an older version of the contract to the current layout.
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn migrate(&mut self) -> Result<()> {
             ensure!(
-                total_assets.unwrap() <= MAX_ASSETS,
-                ProxyError::TooManyAssetsDefined
+                self.proxy.version < STORAGE_VERSION,
+                ProxyError::NothingToMigrate
             );
+            // Steps migrating from older versions go here, in order.
+            if self.proxy.version < 2 {
+                self.migrate_roles();
+                self.proxy.timelock_delay.set(&DEFAULT_TIMELOCK_DELAY);
+                self.migrate_default_collection()?;
+            }
+            self.proxy.version = STORAGE_VERSION;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.proxy.version
+        }
+
+        /// Stops all mints until `unpause` is called.
+        #[ink(message)]
+        #[modifiers(only_role(PAUSER))]
+        pub fn pause(&mut self) -> Result<()> {
+            self._pause::<ProxyError>()?;
+            Self::env().emit_event(Paused {
+                account: Self::env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(PAUSER))]
+        pub fn unpause(&mut self) -> Result<()> {
+            self._unpause::<ProxyError>()?;
+            Self::env().emit_event(Unpaused {
+                account: Self::env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused()
+        }
+
+        #[ink(message)]
+        pub fn payees(&self) -> Vec<(AccountId, u16)> {
+            self.proxy.payees.clone()
+        }
+
+        /// Returns the amount already released to the payee.
+        #[ink(message)]
+        pub fn released(&self, payee: AccountId) -> Balance {
+            self.proxy.released.get(&payee).unwrap_or(0)
+        }
+
+        /// Returns the amount the payee can release.
+        #[ink(message)]
+        pub fn pending(&self, payee: AccountId) -> Result<Balance> {
+            let (_, share) = self
+                .proxy
+                .payees
+                .iter()
+                .find(|(account, _)| *account == payee)
+                .ok_or(ProxyError::NotPayee)?;
+            let total_share =
+                self.proxy.revenue.saturating_mul(*share as Balance) / MAX_BASIS_POINTS;
+            Ok(total_share.saturating_sub(self.released(payee)))
+        }
+
+        #[ink(message)]
+        pub fn mint_commitment(&self, account: AccountId) -> Option<MintCommitment> {
+            self.proxy.mint_commitments.get(&account)
+        }
+
+        #[ink(message)]
+        pub fn reveal_window(&self) -> BlockNumber {
+            self.proxy.reveal_window
+        }
+
+        /// Returns the share of an expired commitment's deposit kept by the proxy,
+        /// in basis points.
+        #[ink(message)]
+        pub fn commitment_penalty(&self) -> u16 {
+            self.proxy
+                .commitment_penalty
+                .get()
+                .unwrap_or(DEFAULT_COMMITMENT_PENALTY)
+        }
+
+        #[ink(message)]
+        pub fn randomness_source(&self) -> RandomnessSource {
+            self.proxy.randomness_source
+        }
+
+        #[ink(message)]
+        pub fn allowlist_root(&self) -> Option<[u8; 32]> {
+            self.proxy.allowlist_root
+        }
+
+        /// Returns number of tokens the account has minted through `allowlist_mint`.
+        #[ink(message)]
+        pub fn allowlist_claimed(&self, account: AccountId) -> u32 {
+            self.proxy.allowlist_claimed.get(&account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn voucher_signer(&self) -> Option<[u8; 33]> {
+            self.proxy.voucher_signer
+        }
+
+        #[ink(message)]
+        pub fn is_voucher_used(&self, nonce: u64) -> bool {
+            self.proxy.used_voucher_nonces.get(&nonce).unwrap_or(false)
+        }
+
+        #[ink(message)]
+        pub fn sale_phases(&self) -> Vec<SalePhase> {
+            self.proxy.sale_phases.clone()
+        }
+
+        /// Returns the sale phase active at the current block timestamp.
+        #[ink(message)]
+        pub fn current_phase(&self) -> Option<SalePhase> {
+            let now = Self::env().block_timestamp();
+            self.proxy
+                .sale_phases
+                .iter()
+                .find(|phase| phase.start <= now && now < phase.end)
+                .cloned()
+        }
+
+        /// Returns number of tokens minted in the phase starting at `phase_start`,
+        /// in total and to the account.
+        #[ink(message)]
+        pub fn phase_minted(&self, phase_start: Timestamp, account: AccountId) -> (u32, u32) {
+            (
+                self.proxy.phase_minted.get(&phase_start).unwrap_or(0),
+                self.proxy
+                    .phase_wallet_minted
+                    .get(&(phase_start, account))
+                    .unwrap_or(0),
+            )
+        }
+
+        /// Returns number of tokens minted to the account.
+        #[ink(message)]
+        pub fn minted_by(&self, account: AccountId) -> u32 {
+            self.proxy.wallet_minted.get(&account).unwrap_or(0)
+        }
+
+        /// Returns the price of a single token paid with the PSP22 token, if the token is accepted.
+        #[ink(message)]
+        pub fn psp22_price(&self, token: AccountId) -> Option<Balance> {
+            self.proxy.psp22_prices.get(&token)
+        }
+
+        #[ink(message)]
+        pub fn refund_overpayment(&self) -> bool {
+            self.proxy.refund_overpayment
+        }
+
+        #[ink(message)]
+        pub fn max_mints_per_wallet(&self) -> u32 {
+            self.proxy.max_mints_per_wallet
+        }
+
+        #[ink(message)]
+        pub fn rmrk_contract_address(&self) -> Result<AccountId> {
+            Ok(self.default_collection()?.rmrk_contract)
+        }
+
+        #[ink(message)]
+        pub fn catalog_contract_address(&self) -> Result<AccountId> {
+            Ok(self.default_collection()?.catalog_contract)
+        }
+
+        #[ink(message)]
+        pub fn mint_price(&self) -> Balance {
+            self.collection(DEFAULT_COLLECTION)
+                .map_or(0, |collection| collection.mint_price)
+        }
+
+        #[ink(message)]
+        pub fn collection(&self, collection_id: CollectionId) -> Option<CollectionConfig> {
+            self.proxy.collections.get(&collection_id)
+        }
+
+        /// Returns price of the next token at the current block timestamp.
+        #[ink(message)]
+        pub fn current_price(&self) -> Balance {
+            let fixed_price = self
+                .current_phase()
+                .map_or(self.mint_price(), |phase| phase.price);
+            self.token_price(fixed_price, 0)
+        }
+
+        /// Returns exact cost of the next `count` tokens at the current block timestamp.
+        #[ink(message)]
+        pub fn quote(&self, count: u32) -> Result<Balance> {
+            let fixed_price = self
+                .current_phase()
+                .map_or(self.mint_price(), |phase| phase.price);
+            self.quote_price(fixed_price, count)
+        }
+
+        /// Returns number of tokens of the default collection minted through the proxy.
+        #[ink(message)]
+        pub fn total_minted(&self) -> u64 {
+            self.proxy.total_minted
+        }
+
+        #[ink(message)]
+        pub fn pricing_strategy(&self) -> PricingStrategy {
+            self.proxy.pricing_strategy.clone()
+        }
+
+        #[ink(message)]
+        pub fn gas_limits(&self) -> GasLimits {
+            self.proxy.gas_limits.clone()
+        }
+
+        #[ink(message)]
+        pub fn max_mints_per_call(&self) -> u32 {
+            self.proxy.max_mints_per_call
+        }
+
+        #[ink(message)]
+        pub fn asset_weights(&self) -> Vec<(AssetId, u32)> {
+            self.proxy.asset_weights.clone()
+        }
+
+        /// Returns the chance of each asset of the default collection being picked on mint,
+        /// in parts per billion. If no weights are set, all assets in the asset pool have
+        /// the same odds.
+        #[ink(message)]
+        pub fn asset_odds(&self) -> Result<Vec<(AssetId, u32)>> {
+            if self.proxy.asset_weights.is_empty() {
+                let asset_pool = self.default_collection()?.asset_pool;
+                ensure!(!asset_pool.is_empty(), ProxyError::NoAssetsDefined);
+                let odds = (ODDS_DENOMINATOR / asset_pool.len() as u64) as u32;
+                return Ok(asset_pool
+                    .into_iter()
+                    .map(|asset_id| (asset_id, odds))
+                    .collect())
+            }
+
+            let total_weight = Self::total_weight(&self.proxy.asset_weights)? as u64;
+            Ok(self
+                .proxy
+                .asset_weights
+                .iter()
+                .map(|(asset_id, weight)| {
+                    (
+                        *asset_id,
+                        (*weight as u64 * ODDS_DENOMINATOR / total_weight) as u32,
+                    )
+                })
+                .collect())
+        }
+
+        /// Proposes a new RMRK contract address, which can be set by `execute_proposal`
+        /// once the timelock delay has passed.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn propose_rmrk_contract(&mut self, new_contract_address: AccountId) -> Result<()> {
+            self.propose(ProposedChange::RmrkContract(new_contract_address))
+        }
+
+        /// Proposes a new catalog contract address, which can be set by `execute_proposal`
+        /// once the timelock delay has passed.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn propose_catalog_contract(&mut self, new_contract_address: AccountId) -> Result<()> {
+            self.propose(ProposedChange::CatalogContract(new_contract_address))
+        }
+
+        /// Proposes a new timelock delay. The current delay applies to this proposal.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn propose_timelock_delay(&mut self, new_delay: Timestamp) -> Result<()> {
+            self.propose(ProposedChange::TimelockDelay(new_delay))
+        }
+
+        /// Applies the pending proposal if its timelock delay has passed.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn execute_proposal(&mut self) -> Result<()> {
+            let proposal = self.proposal().ok_or(ProxyError::NoProposal)?;
+            ensure!(
+                Self::env().block_timestamp() >= proposal.executable_at,
+                ProxyError::ProposalNotReady
+            );
+            self.proxy.proposal.set(&None);
+
+            Self::env().emit_event(ProposalExecuted {
+                change: proposal.change.clone(),
+            });
+            match proposal.change {
+                ProposedChange::RmrkContract(new_address) => {
+                    let mut collection = self.default_collection()?;
+                    let old_address =
+                        core::mem::replace(&mut collection.rmrk_contract, new_address);
+                    self.proxy
+                        .collections
+                        .insert(&DEFAULT_COLLECTION, &collection);
+                    Self::env().emit_event(RmrkContractChanged {
+                        old_address: Some(old_address),
+                        new_address,
+                    });
+                }
+                ProposedChange::CatalogContract(new_address) => {
+                    let mut collection = self.default_collection()?;
+                    let old_address =
+                        core::mem::replace(&mut collection.catalog_contract, new_address);
+                    self.proxy
+                        .collections
+                        .insert(&DEFAULT_COLLECTION, &collection);
+                    Self::env().emit_event(CatalogContractChanged {
+                        old_address: Some(old_address),
+                        new_address,
+                    });
+                }
+                ProposedChange::TimelockDelay(new_delay) => {
+                    self.proxy.timelock_delay.set(&new_delay);
+                }
+                ProposedChange::CodeHash(code_hash) => {
+                    ink::env::set_code_hash(&code_hash).map_err(|_| ProxyError::UpgradeFailed)?;
+                }
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn cancel_proposal(&mut self) -> Result<()> {
+            let proposal = self.proposal().ok_or(ProxyError::NoProposal)?;
+            self.proxy.proposal.set(&None);
+            Self::env().emit_event(ProposalCancelled {
+                change: proposal.change,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn proposal(&self) -> Option<Proposal> {
+            self.proxy.proposal.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn timelock_delay(&self) -> Timestamp {
+            self.proxy.timelock_delay.get_or_default()
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(PRICE_ADMIN))]
+        pub fn set_mint_price(&mut self, new_mint_price: Balance) -> Result<()> {
+            self.update_collection_price(DEFAULT_COLLECTION, new_mint_price)
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(PRICE_ADMIN))]
+        pub fn set_collection_price(
+            &mut self,
+            collection_id: CollectionId,
+            new_mint_price: Balance,
+        ) -> Result<()> {
+            self.update_collection_price(collection_id, new_mint_price)
+        }
+
+        /// Registers a collection and returns its id. Ids are assigned in order and never reused,
+        /// so contract addresses of a registered collection can't be changed by re-registering it.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn register_collection(&mut self, config: CollectionConfig) -> Result<CollectionId> {
+            for (index, asset_id) in config.asset_pool.iter().enumerate() {
+                ensure!(
+                    !config.asset_pool[..index].contains(asset_id),
+                    ProxyError::AssetAlreadyInPool
+                );
+            }
+            for asset_id in config.asset_pool.iter() {
+                self.ensure_asset_exists(config.rmrk_contract, *asset_id)?;
+            }
+
+            let collection_id = self.proxy.next_collection_id.get_or_default();
+            self.proxy.next_collection_id.set(
+                &collection_id
+                    .checked_add(1)
+                    .ok_or(ProxyError::InvalidCollection)?,
+            );
+            self.proxy.collections.insert(&collection_id, &config);
+            Self::env().emit_event(CollectionRegistered {
+                collection_id,
+                rmrk_contract: config.rmrk_contract,
+            });
+            Ok(collection_id)
+        }
+
+        /// Removes a collection from the registry. The default collection can't be retired.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn retire_collection(&mut self, collection_id: CollectionId) -> Result<()> {
+            ensure!(
+                collection_id != DEFAULT_COLLECTION,
+                ProxyError::InvalidCollection
+            );
+            ensure!(
+                self.collection(collection_id).is_some(),
+                ProxyError::UnknownCollection
+            );
+            self.proxy.collections.remove(&collection_id);
+            Self::env().emit_event(CollectionRetired { collection_id });
+            Ok(())
+        }
+
+        /// Pauses or unpauses minting of a single collection.
+        #[ink(message)]
+        #[modifiers(only_role(PAUSER))]
+        pub fn set_collection_paused(
+            &mut self,
+            collection_id: CollectionId,
+            paused: bool,
+        ) -> Result<()> {
+            let mut collection = self
+                .collection(collection_id)
+                .ok_or(ProxyError::UnknownCollection)?;
+            collection.paused = paused;
+            self.proxy.collections.insert(&collection_id, &collection);
+            Self::env().emit_event(CollectionPausedChanged {
+                collection_id,
+                paused,
+            });
+            Ok(())
+        }
+
+        /// Adds an asset to the pool of assets picked on mint of the collection.
+        /// The asset has to be defined in the RMRK contract of the collection.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn add_pool_asset(
+            &mut self,
+            collection_id: CollectionId,
+            asset_id: AssetId,
+        ) -> Result<()> {
+            let mut collection = self
+                .collection(collection_id)
+                .ok_or(ProxyError::UnknownCollection)?;
+            ensure!(
+                !collection.asset_pool.contains(&asset_id),
+                ProxyError::AssetAlreadyInPool
+            );
+            self.ensure_asset_exists(collection.rmrk_contract, asset_id)?;
+
+            collection.asset_pool.push(asset_id);
+            self.proxy.collections.insert(&collection_id, &collection);
+            Ok(())
+        }
+
+        /// Removes an asset from the pool of the collection, together with its weight if the
+        /// collection is the default one. The asset stays in the RMRK contract, e.g. for
+        /// upgrades or reveals, but is no longer picked on mint.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn remove_pool_asset(
+            &mut self,
+            collection_id: CollectionId,
+            asset_id: AssetId,
+        ) -> Result<()> {
+            let mut collection = self
+                .collection(collection_id)
+                .ok_or(ProxyError::UnknownCollection)?;
+            let index = collection
+                .asset_pool
+                .iter()
+                .position(|pool_asset| *pool_asset == asset_id)
+                .ok_or(ProxyError::AssetNotInPool)?;
+
+            collection.asset_pool.remove(index);
+            self.proxy.collections.insert(&collection_id, &collection);
+            if collection_id == DEFAULT_COLLECTION {
+                self.proxy
+                    .asset_weights
+                    .retain(|(weighted_asset, _)| *weighted_asset != asset_id);
+            }
+            Ok(())
+        }
+
+        /// Sets how the mint price is calculated. `PricingStrategy::Fixed` uses the active sale
+        /// phase price or `mint_price`, other strategies override them.
+        /// Bonding curves are based on the number of tokens minted through the proxy.
+        /// Strategies other than `Fixed` require payees.
+        #[ink(message)]
+        #[modifiers(only_role(PRICE_ADMIN))]
+        pub fn set_pricing_strategy(&mut self, pricing_strategy: PricingStrategy) -> Result<()> {
+            match &pricing_strategy {
+                PricingStrategy::DutchAuction(auction) => {
+                    ensure!(
+                        auction.start_price >= auction.floor_price && auction.decay_interval > 0,
+                        ProxyError::InvalidPricingStrategy
+                    );
+                }
+                PricingStrategy::Stepwise(steps) => {
+                    ensure!(
+                        steps
+                            .first()
+                            .map_or(false, |(threshold, _)| *threshold == 0),
+                        ProxyError::InvalidPricingStrategy
+                    );
+                    ensure!(
+                        steps.windows(2).all(|pair| pair[0].0 < pair[1].0),
+                        ProxyError::InvalidPricingStrategy
+                    );
+                }
+                _ => (),
+            }
+            Self::ensure_forwardable_prices(
+                !self.proxy.payees.is_empty(),
+                self.mint_price(),
+                &self.proxy.sale_phases,
+                &pricing_strategy,
+            )?;
+            self.proxy.pricing_strategy = pricing_strategy;
+            Ok(())
+        }
+
+        /// Sets gas limits of calls to the RMRK and PSP22 contracts.
+        /// Zero forwards all remaining gas.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_gas_limits(&mut self, gas_limits: GasLimits) -> Result<()> {
+            self.proxy.gas_limits = gas_limits;
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_max_mints_per_call(&mut self, new_max_mints_per_call: u32) -> Result<()> {
+            self.proxy.max_mints_per_call = new_max_mints_per_call;
+            Ok(())
+        }
+
+        /// Sets accounts sharing the mint revenue, with their shares in basis points.
+        /// Shares have to add up to 10000. While payees are set, the proxy keeps mint proceeds
+        /// and mints for free, so the RMRK contract has to be configured with zero mint price.
+        /// An empty list forwards proceeds to the RMRK contract again, which requires the fixed
+        /// pricing strategy and all sale phases to use the mint price.
+        /// Payees can be changed only after all revenue has been released.
+        #[ink(message)]
+        #[modifiers(only_role(TREASURER))]
+        pub fn set_payees(&mut self, payees: Vec<(AccountId, u16)>) -> Result<()> {
+            if !payees.is_empty() {
+                let total_shares = payees
+                    .iter()
+                    .map(|(_, share)| *share as Balance)
+                    .sum::<Balance>();
+                ensure!(total_shares == MAX_BASIS_POINTS, ProxyError::InvalidPayees);
+            }
+            for (index, (payee, share)) in payees.iter().enumerate() {
+                ensure!(*share > 0, ProxyError::InvalidPayees);
+                ensure!(
+                    !payees[..index].iter().any(|(other, _)| other == payee),
+                    ProxyError::InvalidPayees
+                );
+            }
+            for (payee, _) in self.proxy.payees.iter() {
+                ensure!(self.pending(*payee)? == 0, ProxyError::UnreleasedRevenue);
+            }
+            Self::ensure_forwardable_prices(
+                !payees.is_empty(),
+                self.mint_price(),
+                &self.proxy.sale_phases,
+                &self.proxy.pricing_strategy,
+            )?;
+
+            // Revenue collected while no payees were set, e.g. commitment penalties, goes to
+            // the new payees.
+            if !self.proxy.payees.is_empty() {
+                for (payee, _) in self.proxy.payees.iter() {
+                    self.proxy.released.remove(payee);
+                }
+                self.proxy.revenue = 0;
+            }
+            self.proxy.payees = payees;
+            Ok(())
+        }
+
+        /// Sets the price of a single token paid with the PSP22 token.
+        /// `None` stops accepting the token as payment.
+        #[ink(message)]
+        #[modifiers(only_role(PRICE_ADMIN))]
+        pub fn set_psp22_price(&mut self, token: AccountId, price: Option<Balance>) -> Result<()> {
+            match price {
+                Some(price) => self.proxy.psp22_prices.insert(&token, &price),
+                None => self.proxy.psp22_prices.remove(&token),
+            }
+            Ok(())
+        }
+
+        /// Enables accepting values above the mint price. The excess is returned to the caller.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_refund_overpayment(&mut self, refund_overpayment: bool) -> Result<()> {
+            self.proxy.refund_overpayment = refund_overpayment;
+            Ok(())
+        }
+
+        /// Sets maximum number of tokens minted to a single account. Zero means no limit.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_max_mints_per_wallet(&mut self, new_max_mints_per_wallet: u32) -> Result<()> {
+            self.proxy.max_mints_per_wallet = new_max_mints_per_wallet;
+            Ok(())
+        }
+
+        /// Sets number of blocks after commit in which the mint has to be revealed.
+        /// The runtime has to keep block hashes for at least that many blocks. The window has
+        /// to be at least two blocks, since the earliest reveal is two blocks after the commit.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_reveal_window(&mut self, new_reveal_window: BlockNumber) -> Result<()> {
+            ensure!(new_reveal_window >= 2, ProxyError::InvalidRevealWindow);
+            self.proxy.reveal_window = new_reveal_window;
+            Ok(())
+        }
+
+        /// Sets the share of an expired commitment's deposit kept by the proxy, in basis points.
+        /// Lower penalties make skipping unwanted reveals cheaper.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_commitment_penalty(&mut self, new_commitment_penalty: u16) -> Result<()> {
+            ensure!(
+                new_commitment_penalty as Balance <= MAX_BASIS_POINTS,
+                ProxyError::InvalidCommitmentPenalty
+            );
+            self.proxy.commitment_penalty.set(&new_commitment_penalty);
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_randomness_source(
+            &mut self,
+            new_randomness_source: RandomnessSource,
+        ) -> Result<()> {
+            self.proxy.randomness_source = new_randomness_source;
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_allowlist_root(&mut self, new_allowlist_root: [u8; 32]) -> Result<()> {
+            self.proxy.allowlist_root = Some(new_allowlist_root);
+            Ok(())
+        }
+
+        /// Sets compressed ECDSA public key of the account signing mint vouchers.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_voucher_signer(&mut self, new_voucher_signer: [u8; 33]) -> Result<()> {
+            self.proxy.voucher_signer = Some(new_voucher_signer);
+            Ok(())
+        }
+
+        /// Sets sale phases. Phases have to be ordered by time and must not overlap.
+        /// Phase prices other than `mint_price` require payees.
+        /// Mint counters are kept per phase start, so phases can be edited without resetting them.
+        /// An empty list disables phases and `mint_price` is used at any time.
+        #[ink(message)]
+        #[modifiers(only_role(PRICE_ADMIN))]
+        pub fn set_sale_phases(&mut self, sale_phases: Vec<SalePhase>) -> Result<()> {
+            for (index, phase) in sale_phases.iter().enumerate() {
+                ensure!(phase.start < phase.end, ProxyError::InvalidSalePhases);
+                if index > 0 {
+                    ensure!(
+                        sale_phases[index - 1].end <= phase.start,
+                        ProxyError::InvalidSalePhases
+                    );
+                }
+            }
+            Self::ensure_forwardable_prices(
+                !self.proxy.payees.is_empty(),
+                self.mint_price(),
+                &sale_phases,
+                &self.proxy.pricing_strategy,
+            )?;
+
+            self.proxy.sale_phases = sale_phases;
+            Ok(())
+        }
+
+        /// Sets the weight of each asset picked on mint of the default collection. Weighted assets
+        /// have to be in the asset pool and assets not on the list are never picked.
+        /// An empty list restores uniform distribution across the asset pool.
+        #[ink(message)]
+        #[modifiers(only_role(CONFIG_ADMIN))]
+        pub fn set_asset_weights(&mut self, asset_weights: Vec<(AssetId, u32)>) -> Result<()> {
+            if !asset_weights.is_empty() {
+                Self::total_weight(&asset_weights)?;
+            }
+            let asset_pool = self.default_collection()?.asset_pool;
+            for (index, (asset_id, _)) in asset_weights.iter().enumerate() {
+                ensure!(*asset_id > 0, ProxyError::InvalidAssetWeights);
+                ensure!(asset_pool.contains(asset_id), ProxyError::AssetNotInPool);
+                ensure!(
+                    !asset_weights[..index]
+                        .iter()
+                        .any(|(other_id, _)| other_id == asset_id),
+                    ProxyError::InvalidAssetWeights
+                );
+            }
+
+            self.proxy.asset_weights = asset_weights;
+            Ok(())
+        }
+
+        /// Mints a token of the collection to `recipient`, paid by the caller.
+        fn mint_from(&mut self, collection_id: CollectionId, recipient: AccountId) -> Result<()> {
+            let collection = self.active_collection(collection_id)?;
+            let price = if collection_id == DEFAULT_COLLECTION {
+                self.record_wallet_mints(recipient, 1)?;
+                self.sale_price(recipient, 1, false)?
+            } else {
+                collection.mint_price
+            };
+            let refund = self.check_payment(price)?;
+
+            let asset_id = self.pick_asset(collection_id, &collection, None)?;
+            self.mint_token(collection_id, recipient, price, asset_id, refund, None)?;
+            self.refund(refund)
+        }
+
+        /// Moves the default admin role and the operational roles held by `from` to `to`.
+        fn move_roles(&mut self, from: AccountId, to: AccountId) {
+            for role in [
+                DEFAULT_ADMIN_ROLE,
+                PRICE_ADMIN,
+                CONFIG_ADMIN,
+                PAUSER,
+                TREASURER,
+                MINTER,
+            ] {
+                if AccessControl::has_role(self, role, from) {
+                    self._do_revoke_role(role, from);
+                    self._setup_role(role, to);
+                }
+            }
+        }
+
+        /// Grants the default admin role and the operational roles to the owner. Storage version 1
+        /// has no access control, so nobody would hold a role after the upgrade.
+        fn migrate_roles(&mut self) {
+            let owner = self.owner();
+            for role in [
+                DEFAULT_ADMIN_ROLE,
+                PRICE_ADMIN,
+                CONFIG_ADMIN,
+                PAUSER,
+                TREASURER,
+                MINTER,
+            ] {
+                self._setup_role(role, owner);
+            }
+        }
+
+        /// Registers the contract addresses and the mint price kept by storage version 1
+        /// as collection `0`. Version 1 picked weighted assets if weights were set and any
+        /// asset of the RMRK contract otherwise, so the asset pool is seeded the same way.
+        fn migrate_default_collection(&mut self) -> Result<()> {
+            let rmrk_contract = self.proxy.legacy_rmrk_contract.take();
+            let catalog_contract = self.proxy.legacy_catalog_contract.take();
+            let mint_price = core::mem::take(&mut self.proxy.legacy_mint_price);
+            if let (Some(rmrk_contract), Some(catalog_contract)) = (rmrk_contract, catalog_contract)
+            {
+                let asset_pool = if self.proxy.asset_weights.is_empty() {
+                    (1..=self.total_assets(rmrk_contract)?).collect()
+                } else {
+                    self.proxy
+                        .asset_weights
+                        .iter()
+                        .map(|(asset_id, _)| *asset_id)
+                        .collect()
+                };
+                self.proxy.collections.insert(
+                    &DEFAULT_COLLECTION,
+                    &CollectionConfig {
+                        rmrk_contract,
+                        catalog_contract,
+                        mint_price,
+                        asset_pool,
+                        paused: false,
+                    },
+                );
+                self.proxy.next_collection_id.set(&(DEFAULT_COLLECTION + 1));
+            }
+            Ok(())
+        }
+
+        /// Returns number of asset entries defined in the RMRK contract.
+        fn total_assets(&self, rmrk_contract: AccountId) -> Result<u32> {
+            let selector = ink::selector_bytes!("MultiAsset::total_assets");
+            Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(rmrk_contract)
+                    .gas_limit(self.proxy.gas_limits.query)
+                    .exec_input(ExecutionInput::new(Selector::new(selector)))
+                    .returns::<u32>()
+                    .try_invoke(),
+            )
+        }
+
+        fn default_collection(&self) -> Result<CollectionConfig> {
+            self.collection(DEFAULT_COLLECTION)
+                .ok_or(ProxyError::NotConfigured)
+        }
+
+        /// Returns a registered collection. Fails if minting of the collection is paused.
+        fn active_collection(&self, collection_id: CollectionId) -> Result<CollectionConfig> {
+            let collection = self
+                .collection(collection_id)
+                .ok_or(ProxyError::UnknownCollection)?;
+            ensure!(!collection.paused, ProxyError::CollectionPaused);
+            Ok(collection)
+        }
+
+        fn update_collection_price(
+            &mut self,
+            collection_id: CollectionId,
+            new_mint_price: Balance,
+        ) -> Result<()> {
+            let mut collection = self
+                .collection(collection_id)
+                .ok_or(ProxyError::UnknownCollection)?;
+            if collection_id == DEFAULT_COLLECTION {
+                Self::ensure_forwardable_prices(
+                    !self.proxy.payees.is_empty(),
+                    new_mint_price,
+                    &self.proxy.sale_phases,
+                    &self.proxy.pricing_strategy,
+                )?;
+            }
+            let old_price = core::mem::replace(&mut collection.mint_price, new_mint_price);
+            self.proxy.collections.insert(&collection_id, &collection);
+            Self::env().emit_event(MintPriceChanged {
+                collection_id,
+                old_price,
+                new_price: new_mint_price,
+            });
+            Ok(())
+        }
+
+        /// Stores a proposal executable after the timelock delay. Only one proposal can be pending.
+        fn propose(&mut self, change: ProposedChange) -> Result<()> {
+            ensure!(self.proposal().is_none(), ProxyError::ProposalPending);
+            let executable_at = Self::env()
+                .block_timestamp()
+                .saturating_add(self.timelock_delay());
+            self.proxy.proposal.set(&Some(Proposal {
+                change: change.clone(),
+                executable_at,
+            }));
+            Self::env().emit_event(ProposalCreated {
+                change,
+                executable_at,
+            });
+            Ok(())
+        }
+
+        /// Checks that the fixed pricing strategy is used and all sale phases use the mint price
+        /// unless payees are set. Without payees the price is forwarded to the RMRK contract,
+        /// which accepts only its own price per mint.
+        fn ensure_forwardable_prices(
+            payees_set: bool,
+            mint_price: Balance,
+            sale_phases: &[SalePhase],
+            pricing_strategy: &PricingStrategy,
+        ) -> Result<()> {
+            ensure!(
+                payees_set
+                    || (*pricing_strategy == PricingStrategy::Fixed
+                        && sale_phases.iter().all(|phase| phase.price == mint_price)),
+                ProxyError::PriceRequiresPayees
+            );
+            Ok(())
+        }
+
+        /// Checks that the transferred value matches the price and returns the overpaid amount.
+        /// Overpayment is accepted only if `refund_overpayment` is enabled.
+        fn check_payment(&self, price: Balance) -> Result<Balance> {
+            let transferred_value = Self::env().transferred_value();
+            if self.proxy.refund_overpayment {
+                ensure!(transferred_value >= price, ProxyError::BadMintValue);
+                Ok(transferred_value - price)
+            } else {
+                ensure!(transferred_value == price, ProxyError::BadMintValue);
+                Ok(0)
+            }
+        }
+
+        /// Returns the overpaid amount to the caller.
+        fn refund(&self, amount: Balance) -> Result<()> {
+            if amount > 0 {
+                Self::env()
+                    .transfer(Self::env().caller(), amount)
+                    .map_err(|_| ProxyError::TransferFailed)?;
+            }
+            Ok(())
+        }
+
+        /// Adds `count` to the number of tokens minted to the recipient, which is counted
+        /// instead of the payer so that paying for others doesn't use up the payer's limit.
+        /// Fails if the recipient would go over the per wallet limit.
+        fn record_wallet_mints(&mut self, recipient: AccountId, count: u32) -> Result<()> {
+            let minted = self.minted_by(recipient).saturating_add(count);
+            ensure!(
+                self.proxy.max_mints_per_wallet == 0 || minted <= self.proxy.max_mints_per_wallet,
+                ProxyError::WalletLimitReached
+            );
+            self.proxy.wallet_minted.insert(&recipient, &minted);
+            Ok(())
+        }
+
+        /// Removes a refunded commitment from the mint counters of the committer, including
+        /// the counters of the sale phase starting at `phase_start`.
+        fn release_commitment_mint(&mut self, account: AccountId, phase_start: Option<Timestamp>) {
+            let minted = self.minted_by(account).saturating_sub(1);
+            self.proxy.wallet_minted.insert(&account, &minted);
+            if let Some(phase_start) = phase_start {
+                let (phase_minted, wallet_minted) = self.phase_minted(phase_start, account);
+                self.proxy
+                    .phase_minted
+                    .insert(&phase_start, &phase_minted.saturating_sub(1));
+                self.proxy
+                    .phase_wallet_minted
+                    .insert(&(phase_start, account), &wallet_minted.saturating_sub(1));
+            }
+        }
+
+        /// Returns total price of the next `count` tokens. If sale phases are set, checks that
+        /// `count` tokens can be minted to the recipient in the active phase and records them.
+        fn sale_price(
+            &mut self,
+            recipient: AccountId,
+            count: u32,
+            allowlisted: bool,
+        ) -> Result<Balance> {
+            if self.proxy.sale_phases.is_empty() {
+                return self.quote_price(self.mint_price(), count)
+            }
+
+            let phase = self.current_phase().ok_or_else(|| {
+                let now = Self::env().block_timestamp();
+                if now < self.proxy.sale_phases[0].start {
+                    ProxyError::SaleNotStarted
+                } else if self.proxy.sale_phases.iter().all(|phase| phase.end <= now) {
+                    ProxyError::SaleEnded
+                } else {
+                    ProxyError::NoActiveSalePhase
+                }
+            })?;
+            ensure!(
+                !phase.allowlist_only || allowlisted,
+                ProxyError::AllowlistRequired
+            );
+
+            let (phase_minted, wallet_minted) = self.phase_minted(phase.start, recipient);
+            let phase_minted = phase_minted.saturating_add(count);
+            let wallet_minted = wallet_minted.saturating_add(count);
+            if let Some(supply_cap) = phase.supply_cap {
+                ensure!(phase_minted <= supply_cap, ProxyError::PhaseSupplyReached);
+            }
+            if let Some(wallet_limit) = phase.wallet_limit {
+                ensure!(
+                    wallet_minted <= wallet_limit,
+                    ProxyError::PhaseWalletLimitReached
+                );
+            }
+            self.proxy.phase_minted.insert(&phase.start, &phase_minted);
+            self.proxy
+                .phase_wallet_minted
+                .insert(&(phase.start, recipient), &wallet_minted);
+
+            self.quote_price(phase.price, count)
+        }
+
+        /// Returns total price of the next `count` tokens.
+        fn quote_price(&self, fixed_price: Balance, count: u32) -> Result<Balance> {
+            (0..count)
+                .try_fold(0 as Balance, |total, offset| {
+                    total.checked_add(self.token_price(fixed_price, offset))
+                })
+                .ok_or(ProxyError::BadMintValue)
+        }
+
+        /// Returns the price given by the pricing strategy for the token minted after `offset`
+        /// more tokens. `fixed_price` is used for `PricingStrategy::Fixed`.
+        fn token_price(&self, fixed_price: Balance, offset: u32) -> Balance {
+            let minted = self.proxy.total_minted.saturating_add(offset as u64);
+            match &self.proxy.pricing_strategy {
+                PricingStrategy::Fixed => fixed_price,
+                PricingStrategy::DutchAuction(auction) => {
+                    auction.price_at(Self::env().block_timestamp())
+                }
+                PricingStrategy::Linear {
+                    base_price,
+                    price_increment,
+                } => base_price.saturating_add(price_increment.saturating_mul(minted as Balance)),
+                PricingStrategy::Stepwise(steps) => {
+                    steps
+                        .iter()
+                        .rev()
+                        .find(|(threshold, _)| *threshold <= minted)
+                        .map_or(0, |(_, price)| *price)
+                }
+            }
+        }
+
+        /// Checks that the asset entry is defined in the RMRK contract.
+        fn ensure_asset_exists(&self, rmrk_contract: AccountId, asset_id: AssetId) -> Result<()> {
+            let selector = ink::selector_bytes!("MultiAsset::get_asset_uri");
+            let asset_uri = Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(rmrk_contract)
+                    .gas_limit(self.proxy.gas_limits.query)
+                    .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(asset_id))
+                    .returns::<Option<String>>()
+                    .try_invoke(),
+            )?;
+            ensure!(asset_uri.is_some(), ProxyError::UnknownAsset);
+
+            Ok(())
+        }
+
+        /// Mints a token of the collection, adds the asset to it and transfers the token to `to`.
+        /// Only tokens of the default collection are counted in `total_minted`.
+        /// If payees are set, the proxy keeps `value` as revenue and mints for free,
+        /// otherwise `value` is forwarded to the RMRK contract. Values paid with a PSP22
+        /// `payment_token` are never forwarded.
+        /// Emits `Minted` event with the caller as a payer and `refund` returned to the caller.
+        fn mint_token(
+            &mut self,
+            collection_id: CollectionId,
+            to: AccountId,
+            value: Balance,
+            asset_id: AssetId,
+            refund: Balance,
+            payment_token: Option<AccountId>,
+        ) -> Result<()> {
+            let rmrk_contract = self
+                .collection(collection_id)
+                .ok_or(ProxyError::UnknownCollection)?
+                .rmrk_contract;
+            if collection_id == DEFAULT_COLLECTION {
+                self.proxy.total_minted += 1;
+            }
+            let forwarded_value = if payment_token.is_some() {
+                0
+            } else if self.proxy.payees.is_empty() {
+                value
+            } else {
+                self.proxy.revenue = self.proxy.revenue.saturating_add(value);
+                0
+            };
+
+            let selector = ink::selector_bytes!("MintingLazy::mint");
+            let mint_result = build_call::<DefaultEnvironment>()
+                .call(rmrk_contract)
+                .gas_limit(self.proxy.gas_limits.mint)
+                .transferred_value(forwarded_value)
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .returns::<core::result::Result<(), RmrkError>>()
+                .try_invoke();
+            ink::env::debug_println!("mint_result: {:?}", mint_result);
+            Self::call_result(selector, mint_result)??;
+
+            let token_id = self.last_received_token(rmrk_contract)?;
+
+            let selector = ink::selector_bytes!("MultiAsset::add_asset_to_token");
+            Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(rmrk_contract)
+                    .gas_limit(self.proxy.gas_limits.add_asset_to_token)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(selector))
+                            .push_arg(token_id.clone())
+                            .push_arg(asset_id)
+                            .push_arg(None::<u32>),
+                    )
+                    .returns::<core::result::Result<(), RmrkError>>()
+                    .try_invoke(),
+            )??;
+
+            let selector = ink::selector_bytes!("PSP34::transfer");
+            Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(rmrk_contract)
+                    .gas_limit(self.proxy.gas_limits.transfer)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(selector))
+                            .push_arg(to)
+                            .push_arg(token_id.clone())
+                            .push_arg(Vec::<u8>::new()),
+                    )
+                    .returns::<core::result::Result<(), PSP34Error>>()
+                    .try_invoke(),
+            )?
+            .map_err(RmrkError::PSP34)?;
+
+            Self::env().emit_event(Minted {
+                payer: Self::env().caller(),
+                recipient: to,
+                token_id,
+                asset_id,
+                price: value,
+                refund,
+                payment_token,
+            });
+            Ok(())
+        }
+
+        /// Returns id of the token the proxy received last, which is the token it has just minted.
+        /// PSP34 enumerable storage appends received tokens to the end of the owner's list, so
+        /// the id doesn't depend on total supply, burned tokens or tokens minted by others.
+        fn last_received_token(&self, rmrk_contract: AccountId) -> Result<Id> {
+            let proxy = Self::env().account_id();
+            let selector = ink::selector_bytes!("PSP34::balance_of");
+            let balance = Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(rmrk_contract)
+                    .gas_limit(self.proxy.gas_limits.query)
+                    .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(proxy))
+                    .returns::<u32>()
+                    .try_invoke(),
+            )?;
+            ensure!(balance > 0, ProxyError::MintingError);
+
+            let selector = ink::selector_bytes!("PSP34Enumerable::owners_token_by_index");
+            let token_id = Self::call_result(
+                selector,
+                build_call::<DefaultEnvironment>()
+                    .call(rmrk_contract)
+                    .gas_limit(self.proxy.gas_limits.query)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(selector))
+                            .push_arg(proxy)
+                            .push_arg(balance as u128 - 1),
+                    )
+                    .returns::<core::result::Result<Id, PSP34Error>>()
+                    .try_invoke(),
+            )?
+            .map_err(RmrkError::PSP34)?;
+            Ok(token_id)
+        }
+
+        /// Unwraps the result of a cross contract call made with `selector`.
+        fn call_result<T>(
+            selector: [u8; 4],
+            result: core::result::Result<ink::MessageResult<T>, ink::env::Error>,
+        ) -> Result<T> {
+            result
+                .map_err(|_| ProxyError::CrossContractCallFailed { selector })?
+                .map_err(|_| ProxyError::LangError)
+        }
+
+        /// Picks a random asset of the collection. Asset weights are used for the default
+        /// collection if set. Otherwise all assets in the collection's asset pool have equal
+        /// chance. The asset is drawn from `seed` if given, otherwise from a new random seed.
+        fn pick_asset(
+            &mut self,
+            collection_id: CollectionId,
+            collection: &CollectionConfig,
+            seed: Option<[u8; 32]>,
+        ) -> Result<AssetId> {
+            let seed = match seed {
+                Some(seed) => seed,
+                None => self.next_seed()?,
+            };
+            if collection_id == DEFAULT_COLLECTION && !self.proxy.asset_weights.is_empty() {
+                let total_weight = Self::total_weight(&self.proxy.asset_weights)?;
+                let target = Self::number_from_seed(seed, total_weight - 1);
+                return Self::weighted_asset(&self.proxy.asset_weights, target)
+                    .ok_or(ProxyError::InvalidAssetWeights)
+            }
+
+            ensure!(
+                !collection.asset_pool.is_empty(),
+                ProxyError::NoAssetsDefined
+            );
+            let index = Self::number_from_seed(seed, collection.asset_pool.len() as u32 - 1);
+            Ok(collection.asset_pool[index as usize])
+        }
+
+        /// Returns the seed a commitment is revealed with: Keccak256 of the hash of the block
+        /// following the commit block, followed by the secret. The block hash is fixed once that
+        /// block is sealed, so neither the committer nor the revealer can choose it.
+        fn reveal_seed(commitment: &MintCommitment, secret: &[u8; 32]) -> Result<[u8; 32]> {
+            let block_hash =
+                randomness::fetch_block_hash(commitment.block_number.saturating_add(1))?;
+            let mut input: Vec<u8> = Vec::new();
+            input.extend_from_slice(&block_hash);
+            input.extend_from_slice(secret);
+            Ok(Self::keccak256(&input))
+        }
+
+        fn is_commitment_expired(&self, commitment: &MintCommitment) -> bool {
+            Self::env().block_number()
+                > commitment
+                    .block_number
+                    .saturating_add(self.proxy.reveal_window)
+        }
+
+        /// Calculates Keccak256 hash of the account id followed by the secret.
+        fn commitment_hash(account: &AccountId, secret: &[u8; 32]) -> [u8; 32] {
+            let mut input: Vec<u8> = Vec::new();
+            input.extend_from_slice(account.as_ref());
+            input.extend_from_slice(secret);
+            Self::keccak256(&input)
+        }
+
+        /// Checks that the voucher is valid and signed by the voucher signer and marks its nonce
+        /// as used.
+        fn redeem_voucher(&mut self, voucher: &MintVoucher, signature: &[u8; 65]) -> Result<()> {
+            let signer = self
+                .proxy
+                .voucher_signer
+                .ok_or(ProxyError::VoucherSignerNotSet)?;
+            ensure!(
+                Self::env().block_timestamp() <= voucher.expiry,
+                ProxyError::VoucherExpired
+            );
+            ensure!(
+                !self.is_voucher_used(voucher.nonce),
+                ProxyError::VoucherAlreadyUsed
+            );
+
+            let mut public_key = [0u8; 33];
+            ink::env::ecdsa_recover(signature, &self.voucher_hash(voucher), &mut public_key)
+                .map_err(|_| ProxyError::InvalidVoucherSignature)?;
+            ensure!(public_key == signer, ProxyError::InvalidVoucherSignature);
+            self.proxy.used_voucher_nonces.insert(&voucher.nonce, &true);
+            Ok(())
+        }
+
+        /// Calculates the message hash signed by the voucher signer.
+        fn voucher_hash(&self, voucher: &MintVoucher) -> [u8; 32] {
+            let mut input = scale::Encode::encode(voucher);
+            input.extend_from_slice(Self::env().account_id().as_ref());
+            Self::keccak256(&input)
+        }
+
+        /// Calculates allowlist Merkle tree leaf for the account.
+        fn allowlist_leaf(account: &AccountId, max_allowed: u32) -> [u8; 32] {
+            let mut input: Vec<u8> = Vec::new();
+            input.extend_from_slice(account.as_ref());
+            input.extend_from_slice(&max_allowed.to_be_bytes());
+            Self::keccak256(&input)
+        }
+
+        /// Checks whether `leaf` is part of the Merkle tree with given `root`.
+        fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+            let computed_root = proof.iter().fold(leaf, |node, sibling| {
+                let mut input: Vec<u8> = Vec::new();
+                if node <= *sibling {
+                    input.extend_from_slice(&node);
+                    input.extend_from_slice(sibling);
+                } else {
+                    input.extend_from_slice(sibling);
+                    input.extend_from_slice(&node);
+                }
+                Self::keccak256(&input)
+            });
+            computed_root == root
+        }
+
+        fn keccak256(input: &[u8]) -> [u8; 32] {
+            let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
+            ink::env::hash_bytes::<hash::Keccak256>(input, &mut output);
+            output
+        }
+
+        /// Finds an asset whose cumulative weight range contains `target`.
+        fn weighted_asset(asset_weights: &[(AssetId, u32)], target: u32) -> Option<AssetId> {
+            let mut cumulative_weight: u32 = 0;
+            for (asset_id, weight) in asset_weights {
+                cumulative_weight += weight;
+                if target < cumulative_weight {
+                    return Some(*asset_id)
+                }
+            }
+            None
+        }
+
+        /// Sums asset weights. Fails if the sum is zero or doesn't fit into `u32`.
+        fn total_weight(asset_weights: &[(AssetId, u32)]) -> Result<u32> {
+            let total_weight = asset_weights
+                .iter()
+                .try_fold(0u32, |total, (_, weight)| total.checked_add(*weight))
+                .ok_or(ProxyError::InvalidAssetWeights)?;
+            ensure!(total_weight > 0, ProxyError::InvalidAssetWeights);
+            Ok(total_weight)
+        }
+
+        /// Generates a new random seed from the block timestamp and the salt.
+        /// With `RandomnessSource::ChainExtension` the hashed seed is used as a subject for
+        /// on-chain randomness.
+        fn next_seed(&mut self) -> Result<[u8; 32]> {
+            let seed = self.env().block_timestamp();
+            let mut input: Vec<u8> = Vec::new();
+            input.extend_from_slice(&seed.to_be_bytes());
+            input.extend_from_slice(&self.proxy.salt.to_be_bytes());
+            self.proxy.salt += 1;
+
+            let output = Self::keccak256(&input);
+            if self.proxy.randomness_source == RandomnessSource::ChainExtension {
+                return randomness::fetch_random(output)
+            }
+            Ok(output)
+        }
+
+        /// Derives a number in range `0..=max_value` from the seed.
+        /// Numbers are taken from the whole seed, 8 bytes at a time. Numbers falling into
+        /// the incomplete range at the top of `u64` are rejected so the result is not biased.
+        fn number_from_seed(seed: [u8; 32], max_value: u32) -> u32 {
+            let range = max_value as u64 + 1;
+            let limit = u64::MAX - u64::MAX % range;
+
+            let mut output = seed;
+            loop {
+                for chunk in output.chunks_exact(8) {
+                    let mut bytes = [0u8; 8];
+                    bytes.copy_from_slice(chunk);
+                    let number = u64::from_be_bytes(bytes);
+                    if number < limit {
+                        return (number % range) as u32
+                    }
+                }
+                // All numbers were rejected, hash the output to get new ones.
+                let previous = output;
+                ink::env::hash_bytes::<hash::Keccak256>(&previous, &mut output);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::DutchAuction;
+        use ink::env::test;
+
+        const SECRET: [u8; 32] = [7; 32];
+
+        #[ink::test]
+        fn constructor_works() {
+            let contract = init_contract();
+            assert_eq!(contract.rmrk_contract_address(), Ok(rmrk_address()));
+            assert_eq!(contract.catalog_contract_address(), Ok(catalog_address()));
+            assert_eq!(contract.mint_price(), 1_000_000_000_000_000_000);
+        }
+
+        #[ink::test]
+        fn propose_rmrk_contract_works() {
+            let mut contract = init_contract();
+            let new_rmrk: AccountId = [0x43; 32].into();
+            assert!(contract.propose_rmrk_contract(new_rmrk).is_ok());
+            assert_eq!(
+                contract.proposal(),
+                Some(Proposal {
+                    change: ProposedChange::RmrkContract(new_rmrk),
+                    executable_at: DEFAULT_TIMELOCK_DELAY,
+                })
+            );
+            assert_eq!(
+                contract.execute_proposal(),
+                Err(ProxyError::ProposalNotReady)
+            );
+            assert_eq!(contract.rmrk_contract_address(), Ok(rmrk_address()));
+
+            test::set_block_timestamp::<Environment>(DEFAULT_TIMELOCK_DELAY);
+            assert!(contract.execute_proposal().is_ok());
+            assert_eq!(contract.rmrk_contract_address(), Ok(new_rmrk));
+            assert_eq!(contract.proposal(), None);
+            match last_event() {
+                Event::RmrkContractChanged(event) => {
+                    assert_eq!(event.old_address, Some(rmrk_address()));
+                    assert_eq!(event.new_address, new_rmrk);
+                }
+                _ => panic!("unexpected event"),
+            }
+        }
+
+        #[ink::test]
+        fn propose_catalog_contract_works() {
+            let mut contract = init_contract();
+            let new_catalog: AccountId = [0x43; 32].into();
+            assert!(contract.propose_catalog_contract(new_catalog).is_ok());
+            test::set_block_timestamp::<Environment>(DEFAULT_TIMELOCK_DELAY);
+            assert!(contract.execute_proposal().is_ok());
+            assert_eq!(contract.catalog_contract_address(), Ok(new_catalog));
+            match last_event() {
+                Event::CatalogContractChanged(event) => {
+                    assert_eq!(event.old_address, Some(catalog_address()));
+                    assert_eq!(event.new_address, new_catalog);
+                }
+                _ => panic!("unexpected event"),
+            }
+        }
+
+        #[ink::test]
+        fn propose_timelock_delay_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.timelock_delay(), DEFAULT_TIMELOCK_DELAY);
+            assert!(contract.propose_timelock_delay(1_000).is_ok());
+            test::set_block_timestamp::<Environment>(DEFAULT_TIMELOCK_DELAY);
+            assert!(contract.execute_proposal().is_ok());
+            assert_eq!(contract.timelock_delay(), 1_000);
+            assert!(matches!(last_event(), Event::ProposalExecuted(_)));
+        }
+
+        #[ink::test]
+        fn propose_fails_if_proposal_pending() {
+            let mut contract = init_contract();
+            assert!(contract.propose_timelock_delay(1_000).is_ok());
+            assert_eq!(
+                contract.propose_rmrk_contract(default_accounts().bob),
+                Err(ProxyError::ProposalPending)
+            );
+        }
+
+        #[ink::test]
+        fn cancel_proposal_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.cancel_proposal(), Err(ProxyError::NoProposal));
+            assert!(contract
+                .propose_rmrk_contract(default_accounts().bob)
+                .is_ok());
+            assert!(contract.cancel_proposal().is_ok());
+            assert!(matches!(last_event(), Event::ProposalCancelled(_)));
+            assert_eq!(contract.proposal(), None);
+
+            test::set_block_timestamp::<Environment>(DEFAULT_TIMELOCK_DELAY);
+            assert_eq!(contract.execute_proposal(), Err(ProxyError::NoProposal));
+            assert_eq!(contract.rmrk_contract_address(), Ok(rmrk_address()));
+        }
+
+        #[ink::test]
+        fn proposals_fail_without_role() {
+            let mut contract = init_contract();
+            let missing_role = Err(ProxyError::AccessControlError(
+                AccessControlError::MissingRole,
+            ));
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.propose_rmrk_contract(default_accounts().bob),
+                missing_role
+            );
+            assert_eq!(
+                contract.propose_catalog_contract(default_accounts().bob),
+                missing_role
+            );
+            assert_eq!(contract.propose_timelock_delay(0), missing_role);
+            assert_eq!(contract.execute_proposal(), missing_role);
+            assert_eq!(contract.cancel_proposal(), missing_role);
+        }
+
+        #[ink::test]
+        fn transfer_ownership_works() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            assert!(contract.transfer_ownership(accounts.bob).is_ok());
+            assert_eq!(contract.pending_owner(), Some(accounts.bob));
+            assert_eq!(contract.owner(), accounts.alice);
+            assert!(matches!(last_event(), Event::OwnershipTransferStarted(_)));
+
+            set_sender(accounts.charlie);
+            assert_eq!(
+                contract.accept_ownership(),
+                Err(ProxyError::NotPendingOwner)
+            );
+
+            set_sender(accounts.bob);
+            assert!(contract.accept_ownership().is_ok());
+            assert_eq!(contract.owner(), accounts.bob);
+            assert_eq!(contract.pending_owner(), None);
+            assert!(matches!(last_event(), Event::OwnershipTransferred(_)));
+        }
+
+        #[ink::test]
+        fn accept_ownership_transfers_roles() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            let roles = [
+                DEFAULT_ADMIN_ROLE,
+                PRICE_ADMIN,
+                CONFIG_ADMIN,
+                PAUSER,
+                TREASURER,
+                MINTER,
+            ];
+            assert!(contract.revoke_role(MINTER, accounts.alice).is_ok());
+            assert!(contract.transfer_ownership(accounts.bob).is_ok());
+            set_sender(accounts.bob);
+            assert!(contract.accept_ownership().is_ok());
+
+            for role in roles {
+                assert!(!contract.has_role(role, accounts.alice));
+            }
+            for role in &roles[..5] {
+                assert!(contract.has_role(*role, accounts.bob));
+            }
+            // Roles the previous owner didn't hold are not granted.
+            assert!(!contract.has_role(MINTER, accounts.bob));
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                contract.set_mint_price(100),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+            assert_eq!(
+                contract.grant_role(PAUSER, accounts.alice),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn transfer_ownership_fails_if_not_owner() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.transfer_ownership(default_accounts().bob),
+                Err(ProxyError::OwnableError(OwnableError::CallerIsNotOwner))
+            );
+        }
+
+        #[ink::test]
+        fn set_mint_price_works() {
+            let mut contract = init_contract();
+            assert!(contract.set_mint_price(100).is_ok());
+            assert_eq!(contract.mint_price(), 100);
+            match last_event() {
+                Event::MintPriceChanged(event) => {
+                    assert_eq!(event.collection_id, DEFAULT_COLLECTION);
+                    assert_eq!(event.old_price, 1_000_000_000_000_000_000);
+                    assert_eq!(event.new_price, 100);
+                }
+                _ => panic!("unexpected event"),
+            }
+        }
+
+        #[ink::test]
+        fn set_mint_price_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_mint_price(100),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn contract_addresses_fail_if_not_configured() {
+            let mut contract = init_contract();
+            contract.proxy.collections.remove(&DEFAULT_COLLECTION);
+            assert_eq!(
+                contract.rmrk_contract_address(),
+                Err(ProxyError::NotConfigured)
+            );
+            assert_eq!(
+                contract.catalog_contract_address(),
+                Err(ProxyError::NotConfigured)
+            );
+        }
+
+        #[ink::test]
+        fn mint_fails_if_not_configured() {
+            let mut contract = init_contract();
+            contract.proxy.collections.remove(&DEFAULT_COLLECTION);
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(contract.mint(), Err(ProxyError::UnknownCollection));
+            assert_eq!(contract.asset_odds(), Err(ProxyError::NotConfigured));
+        }
+
+        #[ink::test]
+        fn constructor_registers_default_collection() {
+            let contract = init_contract();
+            assert_eq!(
+                contract.collection(DEFAULT_COLLECTION),
+                Some(collection_config(rmrk_address(), 1_000_000_000_000_000_000))
+            );
+            assert_eq!(contract.collection(1), None);
+        }
+
+        #[ink::test]
+        fn register_collection_works() {
+            let mut contract = init_contract();
+            let config = collection_config([0x43; 32].into(), 100);
+            assert_eq!(contract.register_collection(config.clone()), Ok(1));
+            assert_eq!(contract.collection(1), Some(config.clone()));
+            assert!(matches!(last_event(), Event::CollectionRegistered(_)));
+            assert_eq!(contract.register_collection(config), Ok(2));
+        }
+
+        #[ink::test]
+        fn retire_collection_works() {
+            let mut contract = init_contract();
+            let config = collection_config([0x43; 32].into(), 100);
+            assert_eq!(contract.register_collection(config.clone()), Ok(1));
+            assert!(contract.retire_collection(1).is_ok());
+            assert_eq!(contract.collection(1), None);
+            assert!(matches!(last_event(), Event::CollectionRetired(_)));
+
+            assert_eq!(
+                contract.retire_collection(1),
+                Err(ProxyError::UnknownCollection)
+            );
+            assert_eq!(
+                contract.retire_collection(DEFAULT_COLLECTION),
+                Err(ProxyError::InvalidCollection)
+            );
+            // Retired ids are not reused.
+            assert_eq!(contract.register_collection(config), Ok(2));
+        }
+
+        #[ink::test]
+        fn collection_admin_fails_without_role() {
+            let mut contract = init_contract();
+            let missing_role = Err(ProxyError::AccessControlError(
+                AccessControlError::MissingRole,
+            ));
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.register_collection(collection_config(rmrk_address(), 0)),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+            assert_eq!(contract.retire_collection(1), missing_role);
+            assert_eq!(contract.set_collection_price(0, 1), missing_role);
+            assert_eq!(contract.set_collection_paused(0, true), missing_role);
+        }
+
+        #[ink::test]
+        fn set_collection_price_works() {
+            let mut contract = init_contract();
+            let config = collection_config([0x43; 32].into(), 100);
+            assert_eq!(contract.register_collection(config), Ok(1));
+            assert!(contract.set_collection_price(1, 200).is_ok());
+            assert_eq!(contract.collection(1).unwrap().mint_price, 200);
+            assert_eq!(contract.mint_price(), 1_000_000_000_000_000_000);
+            assert_eq!(
+                contract.set_collection_price(2, 200),
+                Err(ProxyError::UnknownCollection)
+            );
+        }
+
+        #[ink::test]
+        fn mint_collection_fails_with_unknown_collection() {
+            let mut contract = init_contract();
+            set_value_transferred(100);
+            assert_eq!(
+                contract.mint_collection(1),
+                Err(ProxyError::UnknownCollection)
+            );
+        }
+
+        #[ink::test]
+        fn mint_collection_fails_with_bad_value() {
+            let mut contract = init_contract();
+            let config = collection_config([0x43; 32].into(), 100);
+            assert_eq!(contract.register_collection(config), Ok(1));
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(contract.mint_collection(1), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn mint_collection_skips_wallet_limit() {
+            let mut contract = init_contract();
+            let alice = default_accounts().alice;
+            let config = collection_config([0x43; 32].into(), 100);
+            assert_eq!(contract.register_collection(config), Ok(1));
+            assert!(contract.set_max_mints_per_wallet(1).is_ok());
+            contract.proxy.wallet_minted.insert(&alice, &1);
+            set_value_transferred(100);
+
+            // The limit reached in the default collection doesn't apply. The mint fails after
+            // the wallet limit check, as no assets are defined.
+            assert_eq!(
+                contract.mint_collection(1),
+                Err(ProxyError::NoAssetsDefined)
+            );
+            assert_eq!(contract.mint(), Err(ProxyError::WalletLimitReached));
+        }
+
+        #[ink::test]
+        fn mint_fails_if_collection_paused() {
+            let mut contract = init_contract();
+            let config = collection_config([0x43; 32].into(), 100);
+            assert_eq!(contract.register_collection(config), Ok(1));
+            assert!(contract.set_collection_paused(1, true).is_ok());
+            assert!(contract.collection(1).unwrap().paused);
+
+            set_value_transferred(100);
+            assert_eq!(
+                contract.mint_collection(1),
+                Err(ProxyError::CollectionPaused)
+            );
+
+            assert!(contract
+                .set_collection_paused(DEFAULT_COLLECTION, true)
+                .is_ok());
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(contract.mint(), Err(ProxyError::CollectionPaused));
+            assert_eq!(contract.mint_many(1), Err(ProxyError::CollectionPaused));
+        }
+
+        #[ink::test]
+        fn remove_pool_asset_works() {
+            let mut contract = init_contract();
+            set_asset_pool(&mut contract, vec![1, 2, 3]);
+            assert!(contract.set_asset_weights(vec![(1, 1), (2, 1)]).is_ok());
+            assert!(contract.remove_pool_asset(DEFAULT_COLLECTION, 2).is_ok());
+            assert_eq!(
+                contract.collection(DEFAULT_COLLECTION).unwrap().asset_pool,
+                vec![1, 3]
+            );
+            assert_eq!(contract.asset_weights(), vec![(1, 1)]);
+            assert_eq!(
+                contract.remove_pool_asset(DEFAULT_COLLECTION, 2),
+                Err(ProxyError::AssetNotInPool)
+            );
+            assert_eq!(
+                contract.remove_pool_asset(1, 1),
+                Err(ProxyError::UnknownCollection)
+            );
+        }
+
+        #[ink::test]
+        fn add_pool_asset_fails_with_asset_in_pool() {
+            let mut contract = init_contract();
+            set_asset_pool(&mut contract, vec![1]);
+            assert_eq!(
+                contract.add_pool_asset(DEFAULT_COLLECTION, 1),
+                Err(ProxyError::AssetAlreadyInPool)
+            );
+            assert_eq!(
+                contract.add_pool_asset(1, 1),
+                Err(ProxyError::UnknownCollection)
+            );
+
+            let mut config = collection_config([0x43; 32].into(), 100);
+            config.asset_pool = vec![1, 2, 1];
+            assert_eq!(
+                contract.register_collection(config),
+                Err(ProxyError::AssetAlreadyInPool)
+            );
+        }
+
+        #[ink::test]
+        fn asset_pool_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.add_pool_asset(DEFAULT_COLLECTION, 1),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+            assert_eq!(
+                contract.remove_pool_asset(DEFAULT_COLLECTION, 1),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn asset_odds_are_equal_across_pool() {
+            let mut contract = init_contract();
+            assert_eq!(contract.asset_odds(), Err(ProxyError::NoAssetsDefined));
+            set_asset_pool(&mut contract, vec![2, 5]);
+            assert_eq!(
+                contract.asset_odds(),
+                Ok(vec![(2, 500_000_000), (5, 500_000_000)])
+            );
+        }
+
+        #[ink::test]
+        fn mint_fails_with_empty_asset_pool() {
+            let mut contract = init_contract();
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(contract.mint(), Err(ProxyError::NoAssetsDefined));
+        }
+
+        #[ink::test]
+        fn propose_upgrade_works() {
+            let mut contract = init_contract();
+            assert!(contract.propose_upgrade([1; 32]).is_ok());
+            assert_eq!(
+                contract.proposal(),
+                Some(Proposal {
+                    change: ProposedChange::CodeHash([1; 32]),
+                    executable_at: DEFAULT_TIMELOCK_DELAY,
+                })
+            );
+            assert!(matches!(last_event(), Event::ProposalCreated(_)));
+            assert_eq!(
+                contract.execute_proposal(),
+                Err(ProxyError::ProposalNotReady)
+            );
+            assert_eq!(
+                contract.propose_upgrade([2; 32]),
+                Err(ProxyError::ProposalPending)
+            );
+        }
+
+        #[ink::test]
+        fn migrate_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.storage_version(), STORAGE_VERSION);
+            assert_eq!(contract.migrate(), Err(ProxyError::NothingToMigrate));
+
+            contract.proxy.version = 0;
+            assert!(contract.migrate().is_ok());
+            assert_eq!(contract.storage_version(), STORAGE_VERSION);
+        }
+
+        #[ink::test]
+        fn migrate_registers_default_collection() {
+            let mut contract = init_contract();
+            contract.proxy.collections.remove(&DEFAULT_COLLECTION);
+            contract.proxy.next_collection_id.set(&0);
+            contract.proxy.legacy_rmrk_contract = Some(rmrk_address());
+            contract.proxy.legacy_catalog_contract = Some(catalog_address());
+            contract.proxy.legacy_mint_price = 100;
+            contract.proxy.asset_weights = vec![(1, 1), (3, 2)];
+            contract.proxy.version = 1;
+
+            assert!(contract.migrate().is_ok());
+            assert_eq!(
+                contract.collection(DEFAULT_COLLECTION),
+                Some(CollectionConfig {
+                    asset_pool: vec![1, 3],
+                    ..collection_config(rmrk_address(), 100)
+                })
+            );
+            assert_eq!(contract.mint_price(), 100);
+            assert_eq!(contract.proxy.next_collection_id.get(), Some(1));
+            assert_eq!(contract.proxy.legacy_rmrk_contract, None);
+            assert_eq!(contract.proxy.legacy_catalog_contract, None);
+            assert_eq!(contract.proxy.legacy_mint_price, 0);
+        }
+
+        #[ink::test]
+        fn migrate_sets_timelock_delay() {
+            let mut contract = init_contract();
+            contract.proxy.timelock_delay.set(&0);
+            contract.proxy.version = 1;
+
+            assert!(contract.migrate().is_ok());
+            assert_eq!(contract.timelock_delay(), DEFAULT_TIMELOCK_DELAY);
+            assert_eq!(contract.proposal(), None);
+            assert_eq!(contract.pending_owner(), None);
+        }
+
+        #[ink::test]
+        fn version_1_storage_decodes() {
+            let gas_limits = GasLimits {
+                query: 1,
+                mint: 2,
+                add_asset_to_token: 3,
+                transfer: 4,
+            };
+            // Packed fields of storage version 1 in order, mappings take no space.
+            let encoded = scale::Encode::encode(&(
+                (
+                    Some(rmrk_address()),
+                    Some(catalog_address()),
+                    100_u128,
+                    7_u64,
+                    10_u32,
+                    vec![(1_u32, 1_u32)],
+                    50_u32,
+                    RandomnessSource::ChainExtension,
+                    None::<[u8; 32]>,
+                    None::<[u8; 33]>,
+                ),
+                (
+                    Vec::<SalePhase>::new(),
+                    3_u32,
+                    Vec::<(AccountId, u16)>::new(),
+                    0_u128,
+                    true,
+                    PricingStrategy::Fixed,
+                    5_u64,
+                    gas_limits.clone(),
+                    1_u32,
+                ),
+            ));
+
+            let data =
+                <crate::types::Data as ink::storage::traits::Storable>::decode(&mut &encoded[..])
+                    .unwrap();
+            assert_eq!(data.legacy_rmrk_contract, Some(rmrk_address()));
+            assert_eq!(data.legacy_catalog_contract, Some(catalog_address()));
+            assert_eq!(data.legacy_mint_price, 100);
+            assert_eq!(data.salt, 7);
+            assert_eq!(data.asset_weights, vec![(1, 1)]);
+            assert_eq!(data.max_mints_per_wallet, 3);
+            assert!(data.refund_overpayment);
+            assert_eq!(data.total_minted, 5);
+            assert_eq!(data.gas_limits, gas_limits);
+            assert_eq!(data.version, 1);
+        }
+
+        #[ink::test]
+        fn migrate_grants_roles_to_owner() {
+            let mut contract = init_contract();
+            let alice = default_accounts().alice;
+            let roles = [
+                DEFAULT_ADMIN_ROLE,
+                PRICE_ADMIN,
+                CONFIG_ADMIN,
+                PAUSER,
+                TREASURER,
+                MINTER,
+            ];
+            for role in roles {
+                contract._do_revoke_role(role, alice);
+            }
+            assert_eq!(
+                contract.set_mint_price(100),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+            contract.proxy.version = 1;
+
+            assert!(contract.migrate().is_ok());
+            for role in roles {
+                assert!(contract.has_role(role, alice));
+            }
+            assert!(contract.set_mint_price(100).is_ok());
+            assert!(contract.grant_role(PAUSER, default_accounts().bob).is_ok());
+        }
+
+        #[ink::test]
+        fn upgrade_and_migrate_fail_if_not_owner() {
+            let mut contract = init_contract();
+            contract.proxy.version = 0;
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.propose_upgrade([1; 32]),
+                Err(ProxyError::OwnableError(OwnableError::CallerIsNotOwner))
+            );
+            assert_eq!(
+                contract.migrate(),
+                Err(ProxyError::OwnableError(OwnableError::CallerIsNotOwner))
+            );
+        }
+
+        #[ink::test]
+        fn pause_works() {
+            let mut contract = init_contract();
+            assert!(!contract.is_paused());
+            assert!(contract.pause().is_ok());
+            assert!(contract.is_paused());
+            assert!(matches!(last_event(), Event::Paused(_)));
+            assert_eq!(
+                contract.pause(),
+                Err(ProxyError::PausableError(PausableError::Paused))
+            );
+
+            assert!(contract.unpause().is_ok());
+            assert!(!contract.is_paused());
+            assert!(matches!(last_event(), Event::Unpaused(_)));
+            assert_eq!(
+                contract.unpause(),
+                Err(ProxyError::PausableError(PausableError::NotPaused))
+            );
+        }
+
+        #[ink::test]
+        fn pause_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.pause(),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+            assert_eq!(
+                contract.unpause(),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn constructor_grants_roles() {
+            let contract = init_contract();
+            let accounts = default_accounts();
+            for role in [PRICE_ADMIN, CONFIG_ADMIN, PAUSER, TREASURER, MINTER] {
+                assert!(contract.has_role(role, accounts.alice));
+                assert!(!contract.has_role(role, accounts.bob));
+            }
+        }
+
+        #[ink::test]
+        fn grant_and_revoke_role_works() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            assert!(contract.grant_role(PRICE_ADMIN, accounts.bob).is_ok());
+            set_sender(accounts.bob);
+            assert!(contract.set_mint_price(100).is_ok());
+            assert_eq!(
+                contract.set_max_mints_per_call(1),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+
+            set_sender(accounts.alice);
+            assert!(contract.revoke_role(PRICE_ADMIN, accounts.bob).is_ok());
+            set_sender(accounts.bob);
+            assert_eq!(
+                contract.set_mint_price(200),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn grant_role_fails_without_admin_role() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            set_sender(accounts.bob);
+            assert_eq!(
+                contract.grant_role(PRICE_ADMIN, accounts.bob),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn airdrop_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.airdrop(vec![default_accounts().bob]),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn airdrop_fails_with_bad_count() {
+            let mut contract = init_contract();
+            assert_eq!(contract.airdrop(vec![]), Err(ProxyError::BadMintCount));
+            assert_eq!(
+                contract.airdrop(vec![default_accounts().bob; 11]),
+                Err(ProxyError::BadMintCount)
+            );
+        }
+
+        #[ink::test]
+        fn mint_fails_if_paused() {
+            let mut contract = init_contract();
+            assert!(contract.pause().is_ok());
+            set_value_transferred(1_000_000_000_000_000_000);
+            let paused = Err(ProxyError::PausableError(PausableError::Paused));
+            assert_eq!(contract.mint(), paused);
+            assert_eq!(contract.mint_collection(DEFAULT_COLLECTION), paused);
+            assert_eq!(contract.mint_to(default_accounts().bob), paused);
+            assert_eq!(contract.mint_many(1), paused);
+            assert_eq!(contract.allowlist_mint(vec![], 1), paused);
+            assert_eq!(contract.mint_with_voucher(voucher(0, 0), [0; 65]), paused);
+            assert_eq!(contract.mint_with_psp22(psp22_address(), 0), paused);
+            assert_eq!(contract.commit_mint([0; 32]), paused);
+            assert_eq!(contract.reveal_mint(SECRET), paused);
+            assert_eq!(contract.airdrop(vec![default_accounts().bob]), paused);
+        }
+
+        #[ink::test]
+        fn mint_fails_if_no_balance() {
+            let mut contract = init_contract();
+            assert_eq!(contract.mint(), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn mint_to_fails_if_no_balance() {
+            let mut contract = init_contract();
+            assert_eq!(
+                contract.mint_to(default_accounts().bob),
+                Err(ProxyError::BadMintValue)
+            );
+        }
+
+        #[ink::test]
+        fn set_max_mints_per_wallet_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.max_mints_per_wallet(), 0);
+            assert!(contract.set_max_mints_per_wallet(3).is_ok());
+            assert_eq!(contract.max_mints_per_wallet(), 3);
+        }
+
+        #[ink::test]
+        fn set_max_mints_per_wallet_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_max_mints_per_wallet(3),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn mint_fails_if_wallet_limit_reached() {
+            let mut contract = init_contract();
+            assert!(contract.set_max_mints_per_wallet(1).is_ok());
+            set_value_transferred(2_000_000_000_000_000_000);
+            assert_eq!(contract.mint_many(2), Err(ProxyError::WalletLimitReached));
+            assert_eq!(contract.minted_by(default_accounts().alice), 0);
+        }
+
+        #[ink::test]
+        fn mint_to_counts_recipient_mints() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            assert!(contract.set_max_mints_per_wallet(1).is_ok());
+            set_value_transferred(1_000_000_000_000_000_000);
+
+            // The payer's mints don't count. The mint fails after the wallet limit check,
+            // as no assets are defined.
+            contract.proxy.wallet_minted.insert(&accounts.alice, &1);
+            assert_eq!(
+                contract.mint_to(accounts.charlie),
+                Err(ProxyError::NoAssetsDefined)
+            );
+
+            contract.proxy.wallet_minted.insert(&accounts.charlie, &1);
+            assert_eq!(
+                contract.mint_to(accounts.charlie),
+                Err(ProxyError::WalletLimitReached)
+            );
+        }
+
+        #[ink::test]
+        fn record_wallet_mints_works() {
+            let mut contract = init_contract();
+            let charlie = default_accounts().charlie;
+            assert!(contract.set_max_mints_per_wallet(2).is_ok());
+            assert!(contract.record_wallet_mints(charlie, 2).is_ok());
+            assert_eq!(contract.minted_by(charlie), 2);
+            assert_eq!(contract.minted_by(default_accounts().alice), 0);
+            assert_eq!(
+                contract.record_wallet_mints(charlie, 1),
+                Err(ProxyError::WalletLimitReached)
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_voucher_counts_recipient_mints() {
+            let mut contract = init_contract();
+            let voucher = voucher(100, 10);
+            let (signer, signature) = sign_voucher(&contract, &voucher);
+            assert!(contract.set_voucher_signer(signer).is_ok());
+            assert!(contract.set_max_mints_per_wallet(1).is_ok());
+            contract.proxy.wallet_minted.insert(&voucher.recipient, &1);
+
+            set_value_transferred(100);
+            assert_eq!(
+                contract.mint_with_voucher(voucher, signature),
+                Err(ProxyError::WalletLimitReached)
+            );
+        }
+
+        #[ink::test]
+        fn commit_mint_counts_wallet_mints() {
+            let mut contract = init_contract();
+            assert!(contract.set_max_mints_per_wallet(1).is_ok());
+            commit(&mut contract);
+            assert_eq!(contract.minted_by(default_accounts().alice), 1);
+
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(contract.mint(), Err(ProxyError::WalletLimitReached));
+        }
+
+        #[ink::test]
+        fn set_psp22_price_works() {
+            let mut contract = init_contract();
+            let token = psp22_address();
+            assert_eq!(contract.psp22_price(token), None);
+            assert!(contract.set_psp22_price(token, Some(500)).is_ok());
+            assert_eq!(contract.psp22_price(token), Some(500));
+            assert!(contract.set_psp22_price(token, None).is_ok());
+            assert_eq!(contract.psp22_price(token), None);
+        }
+
+        #[ink::test]
+        fn set_psp22_price_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_psp22_price(psp22_address(), Some(500)),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_psp22_fails_if_token_not_accepted() {
+            let mut contract = init_contract();
+            assert_eq!(
+                contract.mint_with_psp22(psp22_address(), 500),
+                Err(ProxyError::Psp22NotAccepted)
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_psp22_fails_with_bad_amount() {
+            let mut contract = init_contract();
+            assert!(contract.set_psp22_price(psp22_address(), Some(500)).is_ok());
+            assert_eq!(
+                contract.mint_with_psp22(psp22_address(), 400),
+                Err(ProxyError::BadMintValue)
+            );
+            assert_eq!(contract.minted_by(default_accounts().alice), 0);
+        }
+
+        #[ink::test]
+        fn withdraw_psp22_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.withdraw_psp22(psp22_address(), 500),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn set_payees_works() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            let payees = vec![(accounts.bob, 7_000), (accounts.charlie, 3_000)];
+            assert!(contract.set_payees(payees.clone()).is_ok());
+            assert_eq!(contract.payees(), payees);
+            assert_eq!(contract.pending(accounts.bob), Ok(0));
+            assert_eq!(contract.pending(accounts.alice), Err(ProxyError::NotPayee));
+        }
+
+        #[ink::test]
+        fn set_payees_fails_with_invalid_payees() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            assert_eq!(
+                contract.set_payees(vec![(accounts.bob, 7_000), (accounts.charlie, 2_000)]),
+                Err(ProxyError::InvalidPayees)
+            );
+            assert_eq!(
+                contract.set_payees(vec![(accounts.bob, 10_000), (accounts.charlie, 0)]),
+                Err(ProxyError::InvalidPayees)
+            );
+            assert_eq!(
+                contract.set_payees(vec![(accounts.bob, 5_000), (accounts.bob, 5_000)]),
+                Err(ProxyError::InvalidPayees)
+            );
+        }
+
+        #[ink::test]
+        fn set_payees_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_payees(vec![]),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn release_works() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            assert!(contract
+                .set_payees(vec![(accounts.bob, 7_000), (accounts.charlie, 3_000)])
+                .is_ok());
+            contract.proxy.revenue = 1_000;
+            test::set_account_balance::<Environment>(contract.env().account_id(), 1_000);
+            let bob_balance = test::get_account_balance::<Environment>(accounts.bob).unwrap();
+
+            assert_eq!(contract.pending(accounts.bob), Ok(700));
+            assert!(contract.release(accounts.bob).is_ok());
+            assert_eq!(contract.released(accounts.bob), 700);
+            assert_eq!(contract.pending(accounts.bob), Ok(0));
+            assert_eq!(
+                test::get_account_balance::<Environment>(accounts.bob).unwrap(),
+                bob_balance + 700
+            );
+            assert_eq!(
+                contract.release(accounts.bob),
+                Err(ProxyError::NothingToRelease)
+            );
+
+            contract.proxy.revenue = 2_000;
+            test::set_account_balance::<Environment>(contract.env().account_id(), 1_300);
+            assert_eq!(contract.pending(accounts.bob), Ok(700));
+            assert_eq!(contract.pending(accounts.charlie), Ok(600));
+        }
+
+        #[ink::test]
+        fn set_payees_fails_with_unreleased_revenue() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            assert!(contract.set_payees(vec![(accounts.bob, 10_000)]).is_ok());
+            contract.proxy.revenue = 1_000;
+            test::set_account_balance::<Environment>(contract.env().account_id(), 1_000);
+
+            assert_eq!(
+                contract.set_payees(vec![(accounts.charlie, 10_000)]),
+                Err(ProxyError::UnreleasedRevenue)
+            );
+            assert!(contract.release(accounts.bob).is_ok());
+            assert!(contract
+                .set_payees(vec![(accounts.charlie, 10_000)])
+                .is_ok());
+            assert_eq!(contract.pending(accounts.charlie), Ok(0));
+        }
+
+        #[ink::test]
+        fn set_refund_overpayment_works() {
+            let mut contract = init_contract();
+            assert!(!contract.refund_overpayment());
+            assert!(contract.set_refund_overpayment(true).is_ok());
+            assert!(contract.refund_overpayment());
+        }
+
+        #[ink::test]
+        fn set_refund_overpayment_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_refund_overpayment(true),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn check_payment_works() {
+            let mut contract = init_contract();
+            set_value_transferred(150);
+            assert_eq!(contract.check_payment(100), Err(ProxyError::BadMintValue));
+            assert_eq!(contract.check_payment(150), Ok(0));
+
+            assert!(contract.set_refund_overpayment(true).is_ok());
+            assert_eq!(contract.check_payment(100), Ok(50));
+            assert_eq!(contract.check_payment(150), Ok(0));
+            assert_eq!(contract.check_payment(200), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn commit_mint_refunds_overpayment() {
+            let mut contract = init_contract();
+            let alice = default_accounts().alice;
+            assert!(contract.set_refund_overpayment(true).is_ok());
+            assert!(contract
+                .set_randomness_source(RandomnessSource::ChainExtension)
+                .is_ok());
+            test::set_account_balance::<Environment>(contract.env().account_id(), 500);
+            let alice_balance = test::get_account_balance::<Environment>(alice).unwrap();
+
+            set_value_transferred(1_000_000_000_000_000_500);
+            let hash = RmrkProxy::commitment_hash(&alice, &SECRET);
+            assert!(contract.commit_mint(hash).is_ok());
+            assert_eq!(
+                contract.mint_commitment(alice).unwrap().deposit,
+                1_000_000_000_000_000_000
+            );
+            assert_eq!(
+                test::get_account_balance::<Environment>(alice).unwrap(),
+                alice_balance + 500
+            );
+        }
+
+        #[ink::test]
+        fn dutch_auction_price_works() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            assert!(contract
+                .set_pricing_strategy(PricingStrategy::DutchAuction(dutch_auction()))
+                .is_ok());
+
+            test::set_block_timestamp::<Environment>(500);
+            assert_eq!(contract.current_price(), 1_000);
+            test::set_block_timestamp::<Environment>(1_999);
+            assert_eq!(contract.current_price(), 1_000);
+            test::set_block_timestamp::<Environment>(2_000);
+            assert_eq!(contract.current_price(), 900);
+            test::set_block_timestamp::<Environment>(5_500);
+            assert_eq!(contract.current_price(), 600);
+            test::set_block_timestamp::<Environment>(100_000);
+            assert_eq!(contract.current_price(), 300);
+        }
+
+        #[ink::test]
+        fn mint_uses_dutch_auction_price() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            assert!(contract
+                .set_pricing_strategy(PricingStrategy::DutchAuction(dutch_auction()))
+                .is_ok());
+            test::set_block_timestamp::<Environment>(2_000);
+            set_value_transferred(1_000);
+            assert_eq!(contract.mint(), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn set_pricing_strategy_fails_with_invalid_auction() {
+            let mut contract = init_contract();
+            let mut auction = dutch_auction();
+            auction.floor_price = 2_000;
+            assert_eq!(
+                contract.set_pricing_strategy(PricingStrategy::DutchAuction(auction)),
+                Err(ProxyError::InvalidPricingStrategy)
+            );
+
+            let mut auction = dutch_auction();
+            auction.decay_interval = 0;
+            assert_eq!(
+                contract.set_pricing_strategy(PricingStrategy::DutchAuction(auction)),
+                Err(ProxyError::InvalidPricingStrategy)
+            );
+        }
+
+        #[ink::test]
+        fn linear_price_works() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            assert!(contract
+                .set_pricing_strategy(PricingStrategy::Linear {
+                    base_price: 100,
+                    price_increment: 10,
+                })
+                .is_ok());
+
+            assert_eq!(contract.current_price(), 100);
+            assert_eq!(contract.quote(3), Ok(100 + 110 + 120));
+            contract.proxy.total_minted = 5;
+            assert_eq!(contract.current_price(), 150);
+            assert_eq!(contract.quote(2), Ok(150 + 160));
+            assert_eq!(contract.quote(0), Ok(0));
+        }
+
+        #[ink::test]
+        fn stepwise_price_works() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            assert!(contract
+                .set_pricing_strategy(PricingStrategy::Stepwise(vec![
+                    (0, 100),
+                    (2, 200),
+                    (4, 300)
+                ]))
+                .is_ok());
+
+            assert_eq!(contract.quote(5), Ok(100 + 100 + 200 + 200 + 300));
+            contract.proxy.total_minted = 3;
+            assert_eq!(contract.current_price(), 200);
+            assert_eq!(contract.quote(3), Ok(200 + 300 + 300));
+        }
+
+        #[ink::test]
+        fn mint_many_uses_bonding_curve_price() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            assert!(contract
+                .set_pricing_strategy(PricingStrategy::Linear {
+                    base_price: 100,
+                    price_increment: 10,
+                })
+                .is_ok());
+            set_value_transferred(200);
+            assert_eq!(contract.mint_many(2), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn set_pricing_strategy_fails_with_invalid_steps() {
+            let mut contract = init_contract();
+            assert_eq!(
+                contract.set_pricing_strategy(PricingStrategy::Stepwise(vec![])),
+                Err(ProxyError::InvalidPricingStrategy)
+            );
+            assert_eq!(
+                contract.set_pricing_strategy(PricingStrategy::Stepwise(vec![(1, 100)])),
+                Err(ProxyError::InvalidPricingStrategy)
+            );
+            assert_eq!(
+                contract.set_pricing_strategy(PricingStrategy::Stepwise(vec![
+                    (0, 100),
+                    (5, 200),
+                    (5, 300)
+                ])),
+                Err(ProxyError::InvalidPricingStrategy)
+            );
+        }
+
+        #[ink::test]
+        fn pricing_strategies_require_payees() {
+            let mut contract = init_contract();
+            let linear = PricingStrategy::Linear {
+                base_price: 100,
+                price_increment: 10,
+            };
+            assert_eq!(
+                contract.set_pricing_strategy(PricingStrategy::DutchAuction(dutch_auction())),
+                Err(ProxyError::PriceRequiresPayees)
+            );
+            assert_eq!(
+                contract.set_pricing_strategy(linear.clone()),
+                Err(ProxyError::PriceRequiresPayees)
+            );
+            assert!(contract
+                .set_pricing_strategy(PricingStrategy::Fixed)
+                .is_ok());
+
+            set_payee(&mut contract);
+            assert!(contract.set_pricing_strategy(linear).is_ok());
+            assert_eq!(
+                contract.set_payees(vec![]),
+                Err(ProxyError::PriceRequiresPayees)
+            );
+        }
+
+        #[ink::test]
+        fn set_pricing_strategy_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_pricing_strategy(PricingStrategy::Fixed),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn set_gas_limits_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.gas_limits().mint, DEFAULT_GAS_LIMIT);
+            let gas_limits = GasLimits {
+                query: 1_000_000_000,
+                mint: 0,
+                add_asset_to_token: 2_000_000_000,
+                transfer: 3_000_000_000,
+            };
+            assert!(contract.set_gas_limits(gas_limits.clone()).is_ok());
+            assert_eq!(contract.gas_limits(), gas_limits);
+        }
+
+        #[ink::test]
+        fn set_gas_limits_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_gas_limits(GasLimits::default()),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn set_max_mints_per_call_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.max_mints_per_call(), DEFAULT_MAX_MINTS_PER_CALL);
+            assert!(contract.set_max_mints_per_call(20).is_ok());
+            assert_eq!(contract.max_mints_per_call(), 20);
+        }
+
+        #[ink::test]
+        fn set_max_mints_per_call_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_max_mints_per_call(20),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn mint_many_fails_with_bad_count() {
+            let mut contract = init_contract();
+            assert_eq!(contract.mint_many(0), Err(ProxyError::BadMintCount));
+            assert_eq!(
+                contract.mint_many(DEFAULT_MAX_MINTS_PER_CALL + 1),
+                Err(ProxyError::BadMintCount)
+            );
+        }
+
+        #[ink::test]
+        fn mint_many_fails_with_bad_value() {
+            let mut contract = init_contract();
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(contract.mint_many(2), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn set_asset_weights_works() {
+            let mut contract = init_contract();
+            set_asset_pool(&mut contract, vec![1, 2, 3, 4]);
+            let weights = vec![(1, 90), (2, 9), (3, 1)];
+            assert!(contract.set_asset_weights(weights.clone()).is_ok());
+            assert_eq!(contract.asset_weights(), weights);
+            assert_eq!(
+                contract.asset_odds(),
+                Ok(vec![(1, 900_000_000), (2, 90_000_000), (3, 10_000_000)])
+            );
+        }
+
+        #[ink::test]
+        fn set_asset_weights_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_asset_weights(vec![(1, 1)]),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn set_asset_weights_fails_with_invalid_weights() {
+            let mut contract = init_contract();
+            set_asset_pool(&mut contract, vec![1, 2]);
+            assert_eq!(
+                contract.set_asset_weights(vec![(1, 0), (2, 0)]),
+                Err(ProxyError::InvalidAssetWeights)
+            );
+            assert_eq!(
+                contract.set_asset_weights(vec![(1, u32::MAX), (2, 1)]),
+                Err(ProxyError::InvalidAssetWeights)
+            );
+            assert_eq!(
+                contract.set_asset_weights(vec![(0, 1)]),
+                Err(ProxyError::InvalidAssetWeights)
+            );
+            assert_eq!(
+                contract.set_asset_weights(vec![(1, 1), (1, 2)]),
+                Err(ProxyError::InvalidAssetWeights)
+            );
+            assert_eq!(
+                contract.set_asset_weights(vec![(1, 1), (3, 1)]),
+                Err(ProxyError::AssetNotInPool)
+            );
+        }
+
+        #[ink::test]
+        fn commit_mint_works() {
+            let mut contract = init_contract();
+            assert!(contract
+                .set_randomness_source(RandomnessSource::ChainExtension)
+                .is_ok());
+            let hash = RmrkProxy::commitment_hash(&default_accounts().alice, &SECRET);
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert!(contract.commit_mint(hash).is_ok());
+            assert_eq!(
+                contract.mint_commitment(default_accounts().alice),
+                Some(MintCommitment {
+                    hash,
+                    block_number: 0,
+                    deposit: 1_000_000_000_000_000_000,
+                    phase_start: None,
+                })
+            );
+            assert_eq!(
+                contract.commit_mint(hash),
+                Err(ProxyError::CommitmentExists)
+            );
+        }
+
+        #[ink::test]
+        fn commit_mint_fails_without_chain_randomness() {
+            let mut contract = init_contract();
+            let hash = RmrkProxy::commitment_hash(&default_accounts().alice, &SECRET);
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(
+                contract.commit_mint(hash),
+                Err(ProxyError::ChainRandomnessRequired)
+            );
+        }
+
+        #[ink::test]
+        fn commit_mint_fails_with_bad_value() {
+            let mut contract = init_contract();
+            assert_eq!(contract.commit_mint([0; 32]), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn reveal_mint_fails_without_commitment() {
+            let mut contract = init_contract();
+            assert_eq!(contract.reveal_mint(SECRET), Err(ProxyError::NoCommitment));
+        }
+
+        #[ink::test]
+        fn reveal_mint_fails_before_next_block_is_sealed() {
+            let mut contract = init_contract();
+            commit(&mut contract);
+            assert_eq!(
+                contract.reveal_mint(SECRET),
+                Err(ProxyError::RevealTooEarly)
+            );
+            test::advance_block::<Environment>();
+            assert_eq!(
+                contract.reveal_mint(SECRET),
+                Err(ProxyError::RevealTooEarly)
+            );
+        }
+
+        #[ink::test]
+        fn reveal_mint_fails_with_invalid_secret() {
+            let mut contract = init_contract();
+            commit(&mut contract);
+            test::advance_block::<Environment>();
+            test::advance_block::<Environment>();
+            assert_eq!(
+                contract.reveal_mint([0; 32]),
+                Err(ProxyError::InvalidSecret)
+            );
+        }
+
+        #[ink::test]
+        fn reveal_mint_fails_if_expired() {
+            let mut contract = init_contract();
+            assert!(contract.set_reveal_window(2).is_ok());
+            commit(&mut contract);
+            for _ in 0..3 {
+                test::advance_block::<Environment>();
+            }
+            assert_eq!(
+                contract.reveal_mint(SECRET),
+                Err(ProxyError::CommitmentExpired)
+            );
+        }
+
+        #[ink::test]
+        fn reveal_seed_does_not_depend_on_reveal_block() {
+            let mut contract = init_contract();
+            test::register_chain_extension(MockedBlockHashExtension);
+            commit(&mut contract);
+            set_asset_pool(&mut contract, (1..=100).collect());
+            let commitment = contract.mint_commitment(default_accounts().alice).unwrap();
+
+            test::advance_block::<Environment>();
+            test::advance_block::<Environment>();
+            let seed = RmrkProxy::reveal_seed(&commitment, &SECRET).unwrap();
+            let collection = contract.default_collection().unwrap();
+            let asset_id = contract
+                .pick_asset(DEFAULT_COLLECTION, &collection, Some(seed))
+                .unwrap();
+
+            // Later blocks, timestamps and salts don't change the picked asset.
+            for _ in 0..10 {
+                test::advance_block::<Environment>();
+                contract.proxy.salt += 1;
+                assert_eq!(RmrkProxy::reveal_seed(&commitment, &SECRET), Ok(seed));
+                assert_eq!(
+                    contract.pick_asset(DEFAULT_COLLECTION, &collection, Some(seed)),
+                    Ok(asset_id)
+                );
+            }
+        }
+
+        #[ink::test]
+        fn refund_commitment_works() {
+            let mut contract = init_contract();
+            assert!(contract.set_reveal_window(2).is_ok());
+            assert!(contract.set_commitment_penalty(2_500).is_ok());
+            commit(&mut contract);
+            test::set_account_balance::<Environment>(
+                contract.env().account_id(),
+                1_000_000_000_000_000_000,
+            );
+            let balance_before =
+                test::get_account_balance::<Environment>(default_accounts().alice).unwrap();
+
+            assert_eq!(
+                contract.refund_commitment(),
+                Err(ProxyError::CommitmentNotExpired)
+            );
+            for _ in 0..3 {
+                test::advance_block::<Environment>();
+            }
+            assert!(contract.refund_commitment().is_ok());
+
+            assert_eq!(contract.mint_commitment(default_accounts().alice), None);
+            assert_eq!(
+                test::get_account_balance::<Environment>(default_accounts().alice).unwrap(),
+                balance_before + 750_000_000_000_000_000
+            );
+            assert_eq!(contract.refund_commitment(), Err(ProxyError::NoCommitment));
+
+            // The penalty goes to the payees set later.
+            set_payee(&mut contract);
+            assert_eq!(
+                contract.pending(default_accounts().django),
+                Ok(250_000_000_000_000_000)
+            );
+        }
+
+        #[ink::test]
+        fn refund_commitment_keeps_deposit_by_default() {
+            let mut contract = init_contract();
+            assert_eq!(contract.commitment_penalty(), DEFAULT_COMMITMENT_PENALTY);
+            commit(&mut contract);
+            let balance_before =
+                test::get_account_balance::<Environment>(default_accounts().alice).unwrap();
+            for _ in 0..=DEFAULT_REVEAL_WINDOW {
+                test::advance_block::<Environment>();
+            }
+
+            assert!(contract.refund_commitment().is_ok());
+            assert_eq!(
+                test::get_account_balance::<Environment>(default_accounts().alice).unwrap(),
+                balance_before
+            );
+            assert_eq!(contract.mint_commitment(default_accounts().alice), None);
+        }
+
+        #[ink::test]
+        fn set_commitment_penalty_works() {
+            let mut contract = init_contract();
+            assert!(contract.set_commitment_penalty(0).is_ok());
+            assert_eq!(contract.commitment_penalty(), 0);
+            assert_eq!(
+                contract.set_commitment_penalty(10_001),
+                Err(ProxyError::InvalidCommitmentPenalty)
+            );
+
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_commitment_penalty(0),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn refund_commitment_releases_mint_counters() {
+            let mut contract = init_contract();
+            let alice = default_accounts().alice;
+            set_payee(&mut contract);
+            let mut phase = sale_phase(0, 1_000_000, 1_000_000_000_000_000_000);
+            phase.supply_cap = Some(1);
+            phase.wallet_limit = Some(1);
+            assert!(contract.set_sale_phases(vec![phase]).is_ok());
+            assert!(contract.set_max_mints_per_wallet(1).is_ok());
+            assert!(contract.set_reveal_window(2).is_ok());
+            test::set_account_balance::<Environment>(
+                contract.env().account_id(),
+                1_000_000_000_000_000_000,
+            );
+
+            commit(&mut contract);
+            assert_eq!(
+                contract.mint_commitment(alice).unwrap().phase_start,
+                Some(0)
+            );
+            assert_eq!(contract.minted_by(alice), 1);
+            assert_eq!(contract.phase_minted(0, alice), (1, 1));
+
+            for _ in 0..3 {
+                test::advance_block::<Environment>();
+            }
+            assert!(contract.refund_commitment().is_ok());
+            assert_eq!(contract.minted_by(alice), 0);
+            assert_eq!(contract.phase_minted(0, alice), (0, 0));
+
+            // The refunded mint doesn't block a new one.
+            commit(&mut contract);
+            assert_eq!(contract.phase_minted(0, alice), (1, 1));
+        }
+
+        #[ink::test]
+        fn set_reveal_window_fails_below_two_blocks() {
+            let mut contract = init_contract();
+            for window in [0, 1] {
+                assert_eq!(
+                    contract.set_reveal_window(window),
+                    Err(ProxyError::InvalidRevealWindow)
+                );
+            }
+            assert!(contract.set_reveal_window(2).is_ok());
+        }
+
+        #[ink::test]
+        fn set_reveal_window_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_reveal_window(10),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn set_allowlist_root_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.allowlist_root(), None);
+            assert!(contract.set_allowlist_root([1; 32]).is_ok());
+            assert_eq!(contract.allowlist_root(), Some([1; 32]));
+        }
+
+        #[ink::test]
+        fn set_allowlist_root_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_allowlist_root([1; 32]),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn verify_merkle_proof_works() {
+            let accounts = default_accounts();
+            let leaves = [
+                RmrkProxy::allowlist_leaf(&accounts.alice, 2),
+                RmrkProxy::allowlist_leaf(&accounts.bob, 1),
+                RmrkProxy::allowlist_leaf(&accounts.charlie, 3),
+            ];
+            let (root, proofs) = merkle_tree(&leaves);
+
+            for (leaf, proof) in leaves.iter().zip(proofs.iter()) {
+                assert!(RmrkProxy::verify_merkle_proof(proof, root, *leaf));
+            }
+            assert!(!RmrkProxy::verify_merkle_proof(
+                &proofs[0],
+                root,
+                RmrkProxy::allowlist_leaf(&accounts.alice, 3)
+            ));
+            assert!(!RmrkProxy::verify_merkle_proof(&proofs[0], root, leaves[1]));
+        }
+
+        #[ink::test]
+        fn allowlist_mint_fails_if_root_not_set() {
+            let mut contract = init_contract();
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(
+                contract.allowlist_mint(vec![], 1),
+                Err(ProxyError::AllowlistNotSet)
+            );
+        }
+
+        #[ink::test]
+        fn allowlist_mint_fails_with_invalid_proof() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            let leaves = [
+                RmrkProxy::allowlist_leaf(&accounts.alice, 2),
+                RmrkProxy::allowlist_leaf(&accounts.bob, 1),
+            ];
+            let (root, proofs) = merkle_tree(&leaves);
+            assert!(contract.set_allowlist_root(root).is_ok());
+
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(
+                contract.allowlist_mint(proofs[0].clone(), 5),
+                Err(ProxyError::NotAllowlisted)
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(
+                contract.allowlist_mint(proofs[1].clone(), 1),
+                Err(ProxyError::NotAllowlisted)
+            );
+        }
+
+        #[ink::test]
+        fn allowlist_mint_fails_if_limit_reached() {
+            let mut contract = init_contract();
+            let accounts = default_accounts();
+            let leaves = [
+                RmrkProxy::allowlist_leaf(&accounts.alice, 0),
+                RmrkProxy::allowlist_leaf(&accounts.bob, 1),
+            ];
+            let (root, proofs) = merkle_tree(&leaves);
+            assert!(contract.set_allowlist_root(root).is_ok());
+
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(
+                contract.allowlist_mint(proofs[0].clone(), 0),
+                Err(ProxyError::AllowlistLimitReached)
+            );
+            assert_eq!(contract.allowlist_claimed(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn allowlist_mint_fails_with_bad_value() {
+            let mut contract = init_contract();
+            assert!(contract.set_allowlist_root([1; 32]).is_ok());
+            assert_eq!(
+                contract.allowlist_mint(vec![], 1),
+                Err(ProxyError::BadMintValue)
+            );
+        }
+
+        #[ink::test]
+        fn set_voucher_signer_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.voucher_signer(), None);
+            assert!(contract.set_voucher_signer([2; 33]).is_ok());
+            assert_eq!(contract.voucher_signer(), Some([2; 33]));
+        }
+
+        #[ink::test]
+        fn set_voucher_signer_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_voucher_signer([2; 33]),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_voucher_fails_if_signer_not_set() {
+            let mut contract = init_contract();
+            set_value_transferred(100);
+            assert_eq!(
+                contract.mint_with_voucher(voucher(100, 10), [0; 65]),
+                Err(ProxyError::VoucherSignerNotSet)
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_voucher_fails_with_bad_value() {
+            let mut contract = init_contract();
+            assert!(contract.set_voucher_signer([2; 33]).is_ok());
+            set_value_transferred(99);
+            assert_eq!(
+                contract.mint_with_voucher(voucher(100, 10), [0; 65]),
+                Err(ProxyError::BadMintValue)
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_voucher_fails_if_expired() {
+            let mut contract = init_contract();
+            assert!(contract.set_voucher_signer([2; 33]).is_ok());
+            test::set_block_timestamp::<Environment>(11);
+            set_value_transferred(100);
+            assert_eq!(
+                contract.mint_with_voucher(voucher(100, 10), [0; 65]),
+                Err(ProxyError::VoucherExpired)
+            );
+        }
+
+        #[ink::test]
+        fn mint_with_voucher_fails_with_invalid_signature() {
+            let mut contract = init_contract();
+            assert!(contract.set_voucher_signer([2; 33]).is_ok());
+            set_value_transferred(100);
+            assert_eq!(
+                contract.mint_with_voucher(voucher(100, 10), [1; 65]),
+                Err(ProxyError::InvalidVoucherSignature)
+            );
+            assert!(!contract.is_voucher_used(1));
+        }
+
+        #[ink::test]
+        fn redeem_voucher_works() {
+            let mut contract = init_contract();
+            let voucher = voucher(100, 10);
+            let (signer, signature) = sign_voucher(&contract, &voucher);
+            assert!(contract.set_voucher_signer(signer).is_ok());
+
+            assert!(contract.redeem_voucher(&voucher, &signature).is_ok());
+            assert!(contract.is_voucher_used(voucher.nonce));
+            assert_eq!(
+                contract.redeem_voucher(&voucher, &signature),
+                Err(ProxyError::VoucherAlreadyUsed)
+            );
+        }
+
+        #[ink::test]
+        fn redeem_voucher_fails_if_signed_by_other_key() {
+            let mut contract = init_contract();
+            let voucher = voucher(100, 10);
+            let (signer, signature) = sign_voucher(&contract, &voucher);
+            assert!(contract.set_voucher_signer([2; 33]).is_ok());
+            assert_eq!(
+                contract.redeem_voucher(&voucher, &signature),
+                Err(ProxyError::InvalidVoucherSignature)
+            );
+
+            // A signature of another voucher doesn't match either.
+            assert!(contract.set_voucher_signer(signer).is_ok());
+            let mut other_voucher = voucher;
+            other_voucher.price = 1;
+            assert_eq!(
+                contract.redeem_voucher(&other_voucher, &signature),
+                Err(ProxyError::InvalidVoucherSignature)
+            );
+            assert!(!contract.is_voucher_used(other_voucher.nonce));
+        }
+
+        #[ink::test]
+        fn mint_with_voucher_fails_if_voucher_used() {
+            let mut contract = init_contract();
+            let voucher = voucher(100, 10);
+            let (signer, signature) = sign_voucher(&contract, &voucher);
+            assert!(contract.set_voucher_signer(signer).is_ok());
+            assert!(contract.redeem_voucher(&voucher, &signature).is_ok());
+
+            set_value_transferred(100);
+            assert_eq!(
+                contract.mint_with_voucher(voucher, signature),
+                Err(ProxyError::VoucherAlreadyUsed)
+            );
+        }
+
+        #[ink::test]
+        fn set_sale_phases_works() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            let phases = vec![sale_phase(10, 20, 100), sale_phase(20, 30, 200)];
+            assert!(contract.set_sale_phases(phases.clone()).is_ok());
+            assert_eq!(contract.sale_phases(), phases);
+
+            assert_eq!(contract.current_phase(), None);
+            test::set_block_timestamp::<Environment>(10);
+            assert_eq!(contract.current_phase(), Some(phases[0].clone()));
+            test::set_block_timestamp::<Environment>(25);
+            assert_eq!(contract.current_phase(), Some(phases[1].clone()));
+            test::set_block_timestamp::<Environment>(30);
+            assert_eq!(contract.current_phase(), None);
+        }
+
+        #[ink::test]
+        fn set_sale_phases_fails_with_invalid_phases() {
+            let mut contract = init_contract();
+            assert_eq!(
+                contract.set_sale_phases(vec![sale_phase(10, 10, 100)]),
+                Err(ProxyError::InvalidSalePhases)
+            );
+            assert_eq!(
+                contract.set_sale_phases(vec![sale_phase(10, 20, 100), sale_phase(15, 30, 200)]),
+                Err(ProxyError::InvalidSalePhases)
+            );
+        }
+
+        #[ink::test]
+        fn sale_phase_prices_require_payees() {
+            let mut contract = init_contract();
+            let mint_price = contract.mint_price();
+            assert_eq!(
+                contract.set_sale_phases(vec![sale_phase(10, 20, 100)]),
+                Err(ProxyError::PriceRequiresPayees)
+            );
+            assert!(contract
+                .set_sale_phases(vec![sale_phase(10, 20, mint_price)])
+                .is_ok());
+            assert_eq!(
+                contract.set_mint_price(100),
+                Err(ProxyError::PriceRequiresPayees)
+            );
+
+            set_payee(&mut contract);
+            assert!(contract
+                .set_sale_phases(vec![sale_phase(10, 20, 100)])
+                .is_ok());
+            assert!(contract.set_mint_price(200).is_ok());
+            assert_eq!(
+                contract.set_payees(vec![]),
+                Err(ProxyError::PriceRequiresPayees)
+            );
+        }
+
+        #[ink::test]
+        fn set_sale_phases_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_sale_phases(vec![]),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn mint_fails_outside_sale_phases() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            assert!(contract
+                .set_sale_phases(vec![sale_phase(10, 20, 100), sale_phase(30, 40, 200)])
+                .is_ok());
+            set_value_transferred(100);
+
+            assert_eq!(contract.mint(), Err(ProxyError::SaleNotStarted));
+            test::set_block_timestamp::<Environment>(25);
+            assert_eq!(contract.mint(), Err(ProxyError::NoActiveSalePhase));
+            test::set_block_timestamp::<Environment>(40);
+            assert_eq!(contract.mint(), Err(ProxyError::SaleEnded));
+        }
+
+        #[ink::test]
+        fn mint_uses_phase_price() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            assert!(contract
+                .set_sale_phases(vec![sale_phase(10, 20, 100)])
+                .is_ok());
+            test::set_block_timestamp::<Environment>(10);
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert_eq!(contract.mint(), Err(ProxyError::BadMintValue));
+        }
+
+        #[ink::test]
+        fn mint_fails_in_allowlist_phase() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            let mut phase = sale_phase(0, 20, 100);
+            phase.allowlist_only = true;
+            assert!(contract.set_sale_phases(vec![phase]).is_ok());
+            set_value_transferred(100);
+            assert_eq!(contract.mint(), Err(ProxyError::AllowlistRequired));
+        }
+
+        #[ink::test]
+        fn mint_many_fails_over_phase_limits() {
+            let mut contract = init_contract();
+            set_payee(&mut contract);
+            let mut phase = sale_phase(0, 20, 100);
+            phase.wallet_limit = Some(1);
+            assert!(contract.set_sale_phases(vec![phase.clone()]).is_ok());
+            set_value_transferred(200);
+            assert_eq!(
+                contract.mint_many(2),
+                Err(ProxyError::PhaseWalletLimitReached)
+            );
+
+            phase.supply_cap = Some(1);
+            assert!(contract.set_sale_phases(vec![phase]).is_ok());
+            assert_eq!(contract.mint_many(2), Err(ProxyError::PhaseSupplyReached));
+        }
+
+        #[ink::test]
+        fn number_from_seed_stays_in_range() {
+            let mut contract = init_contract();
+            for max_value in [0, 1, 254, 255, 256, 10_000, u32::MAX / 2, u32::MAX] {
+                for _ in 0..10 {
+                    let seed = contract.next_seed().unwrap();
+                    assert!(RmrkProxy::number_from_seed(seed, max_value) <= max_value);
+                }
+            }
+            // All numbers taken from this seed are rejected for a range of one.
+            assert_eq!(RmrkProxy::number_from_seed([0xff; 32], 0), 0);
+        }
+
+        #[ink::test]
+        fn number_from_seed_covers_whole_range() {
+            let mut contract = init_contract();
+            let mut hits = [0u32; 1_000];
+            for _ in 0..20_000 {
+                let seed = contract.next_seed().unwrap();
+                hits[RmrkProxy::number_from_seed(seed, 999) as usize] += 1;
+            }
+            assert!(hits.iter().all(|count| *count > 0));
+        }
+
+        #[ink::test]
+        fn next_seed_changes_with_salt() {
+            let mut contract = init_contract();
+            let seed = contract.next_seed().unwrap();
+            assert_eq!(contract.proxy.salt, 1);
+            assert_ne!(contract.next_seed().unwrap(), seed);
+        }
+
+        #[ink::test]
+        fn set_randomness_source_works() {
+            let mut contract = init_contract();
+            assert_eq!(contract.randomness_source(), RandomnessSource::PseudoRandom);
+            assert!(contract
+                .set_randomness_source(RandomnessSource::ChainExtension)
+                .is_ok());
+            assert_eq!(
+                contract.randomness_source(),
+                RandomnessSource::ChainExtension
+            );
+        }
+
+        #[ink::test]
+        fn set_randomness_source_fails_without_role() {
+            let mut contract = init_contract();
+            set_sender(default_accounts().bob);
+            assert_eq!(
+                contract.set_randomness_source(RandomnessSource::ChainExtension),
+                Err(ProxyError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn next_seed_uses_chain_extension() {
+            let mut contract = init_contract();
+            test::register_chain_extension(MockedRandomExtension { status_code: 0 });
+            assert!(contract
+                .set_randomness_source(RandomnessSource::ChainExtension)
+                .is_ok());
+            assert_eq!(contract.next_seed(), Ok([1; 32]));
+
+            // The first 8 bytes of the mocked output are below the rejection limit.
+            let expected = (u64::from_be_bytes([1; 8]) % 1_000) as u32;
+            assert_eq!(RmrkProxy::number_from_seed([1; 32], 999), expected);
+        }
+
+        #[ink::test]
+        fn next_seed_fails_if_chain_extension_fails() {
+            let mut contract = init_contract();
+            test::register_chain_extension(MockedRandomExtension { status_code: 1 });
+            assert!(contract
+                .set_randomness_source(RandomnessSource::ChainExtension)
+                .is_ok());
+            assert_eq!(contract.next_seed(), Err(ProxyError::RandomnessUnavailable));
+        }
+
+        #[ink::test]
+        fn weighted_asset_works() {
+            let weights = vec![(1, 5), (2, 0), (3, 10)];
+            assert_eq!(RmrkProxy::weighted_asset(&weights, 0), Some(1));
+            assert_eq!(RmrkProxy::weighted_asset(&weights, 4), Some(1));
+            assert_eq!(RmrkProxy::weighted_asset(&weights, 5), Some(3));
+            assert_eq!(RmrkProxy::weighted_asset(&weights, 14), Some(3));
+            assert_eq!(RmrkProxy::weighted_asset(&weights, 15), None);
+        }
+
+        /// Randomness chain extension returning a fixed seed.
+        struct MockedRandomExtension {
+            status_code: u32,
+        }
+
+        impl test::ChainExtension for MockedRandomExtension {
+            fn func_id(&self) -> u32 {
+                randomness::FETCH_RANDOM_EXTENSION_ID
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                scale::Encode::encode_to(&[1u8; 32], output);
+                self.status_code
+            }
+        }
+
+        /// Block hash chain extension returning a hash derived from the block number.
+        struct MockedBlockHashExtension;
+
+        impl test::ChainExtension for MockedBlockHashExtension {
+            fn func_id(&self) -> u32 {
+                randomness::FETCH_BLOCK_HASH_EXTENSION_ID
+            }
+
+            fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+                let block_hash = RmrkProxy::keccak256(input);
+                scale::Encode::encode_to(&block_hash, output);
+                0
+            }
+        }
+
+        /// Builds a Merkle tree with sorted pair hashing.
+        /// Returns the root and a proof for each leaf.
+        fn merkle_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+            let mut proofs = vec![Vec::new(); leaves.len()];
+            let mut positions: Vec<usize> = (0..leaves.len()).collect();
+            let mut level = leaves.to_vec();
+            while level.len() > 1 {
+                for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+                    let sibling = *position ^ 1;
+                    if sibling < level.len() {
+                        proof.push(level[sibling]);
+                    }
+                    *position /= 2;
+                }
+                level = level
+                    .chunks(2)
+                    .map(|pair| {
+                        match pair {
+                            [left, right] => {
+                                let (first, second) = if left <= right {
+                                    (left, right)
+                                } else {
+                                    (right, left)
+                                };
+                                RmrkProxy::keccak256(&[&first[..], &second[..]].concat())
+                            }
+                            [single] => *single,
+                            _ => unreachable!(),
+                        }
+                    })
+                    .collect();
+            }
+            (level[0], proofs)
+        }
+
+        fn dutch_auction() -> DutchAuction {
+            DutchAuction {
+                start_time: 1_000,
+                start_price: 1_000,
+                floor_price: 300,
+                price_decay: 100,
+                decay_interval: 1_000,
+            }
+        }
+
+        fn sale_phase(start: Timestamp, end: Timestamp, price: Balance) -> SalePhase {
+            SalePhase {
+                start,
+                end,
+                price,
+                wallet_limit: None,
+                allowlist_only: false,
+                supply_cap: None,
+            }
+        }
+
+        /// Sets a single payee, so the proxy keeps proceeds and any price can be used.
+        fn set_payee(contract: &mut RmrkProxy) {
+            assert!(contract
+                .set_payees(vec![(default_accounts().django, 10_000)])
+                .is_ok());
+        }
+
+        fn voucher(price: Balance, expiry: Timestamp) -> MintVoucher {
+            MintVoucher {
+                recipient: default_accounts().bob,
+                price,
+                nonce: 1,
+                expiry,
+            }
+        }
+
+        /// Signs the voucher with a dev key. Returns the compressed public key and the signature.
+        fn sign_voucher(contract: &RmrkProxy, voucher: &MintVoucher) -> ([u8; 33], [u8; 65]) {
+            let secret_key = secp256k1::SecretKey::from_slice(&[0x11; 32]).unwrap();
+            let public_key =
+                secp256k1::PublicKey::from_secret_key(secp256k1::SECP256K1, &secret_key);
+            let message = secp256k1::Message::from_slice(&contract.voucher_hash(voucher)).unwrap();
+            let (recovery_id, compact) = secp256k1::SECP256K1
+                .sign_ecdsa_recoverable(&message, &secret_key)
+                .serialize_compact();
+
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            (public_key.serialize(), signature)
+        }
+
+        fn commit(contract: &mut RmrkProxy) {
+            assert!(contract
+                .set_randomness_source(RandomnessSource::ChainExtension)
+                .is_ok());
+            let hash = RmrkProxy::commitment_hash(&default_accounts().alice, &SECRET);
+            set_value_transferred(1_000_000_000_000_000_000);
+            assert!(contract.commit_mint(hash).is_ok());
+            set_value_transferred(0);
+        }
+
+        fn init_contract() -> RmrkProxy {
+            set_sender(default_accounts().alice);
+            RmrkProxy::new(rmrk_address(), catalog_address(), 1_000_000_000_000_000_000)
+        }
+
+        fn rmrk_address() -> AccountId {
+            AccountId::from([0x42; 32])
+        }
+
+        fn catalog_address() -> AccountId {
+            AccountId::from([0x41; 32])
+        }
+
+        fn collection_config(rmrk_contract: AccountId, mint_price: Balance) -> CollectionConfig {
+            CollectionConfig {
+                rmrk_contract,
+                catalog_contract: catalog_address(),
+                mint_price,
+                asset_pool: Vec::new(),
+                paused: false,
+            }
+        }
+
+        fn set_asset_pool(contract: &mut RmrkProxy, asset_pool: Vec<AssetId>) {
+            let mut collection = contract.collection(DEFAULT_COLLECTION).unwrap();
+            collection.asset_pool = asset_pool;
+            contract
+                .proxy
+                .collections
+                .insert(&DEFAULT_COLLECTION, &collection);
+        }
+
+        fn psp22_address() -> AccountId {
+            AccountId::from([0x44; 32])
+        }
+
+        fn last_event() -> Event {
+            let event = test::recorded_events()
+                .last()
+                .expect("no event was emitted");
+            <Event as scale::Decode>::decode(&mut &event.data[..]).expect("invalid event data")
+        }
+
+        fn default_accounts() -> test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            test::default_accounts::<Environment>()
+        }
+
+        fn set_sender(sender: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(sender);
+        }
+
+        fn set_value_transferred(value: Balance) {
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(value);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use crate::{
+            proxy::rmrk_proxy::RmrkProxyRef,
+            ProxyError,
+        };
+        use catalog_example::catalog_example::CatalogContractRef;
+        use ink::primitives::AccountId;
+        use ink_e2e::build_message;
+        use openbrush::contracts::psp34::{
+            psp34_external::PSP34,
+            Id,
+        };
+        use rmrk::{
+            storage::catalog_external::Catalog,
+            traits::{
+                minting_lazy_external::MintingLazy,
+                multiasset_external::MultiAsset,
+            },
+            types::{
+                Part,
+                PartType,
+            },
+        };
+        use rmrk_equippable_lazy::rmrk_equippable_lazy::RmrkRef;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn mint_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
+
+            let (rmrk_address, proxy_address) = deploy_contracts(&mut client).await;
+
+            // Mint token.
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            client
+                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Mint failed");
+
+            // Check if token was minted
+            let read_total_supply_message =
+                build_message::<RmrkRef>(rmrk_address.clone()).call(|rmrk| rmrk.total_supply());
+
+            let read_total_supply_result = client
+                .call_dry_run(&alice, &read_total_supply_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(read_total_supply_result, 1);
+
+            // Check if asset has been added to the token.
+            let read_total_assets_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.total_token_assets(Id::U64(1)));
+
+            let read_total_assets_result = client
+                .call_dry_run(&alice, &read_total_assets_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            // ink::env::debug_println!("token assets: {:?}", read_total_assets_result);
+            assert_eq!(read_total_assets_result.0, 1);
+
+            // Check if token owner is same as the caller.
+            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.owner_of(Id::U64(1)));
+
+            let read_owner_of_result = client
+                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+
+            let alice_account_id_32 = alice.account_id();
+            let alice_account_id = AccountId::try_from(alice_account_id_32.as_ref()).unwrap();
+            assert_eq!(read_owner_of_result, alice_account_id);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn mint_resolves_token_id_with_tokens_held_by_proxy(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
+            let alice_account_id = AccountId::try_from(alice.account_id().as_ref()).unwrap();
+            let (rmrk_address, proxy_address) = deploy_contracts(&mut client).await;
+
+            // Mint token 1 and send it back to the proxy, so the proxy holds a token while minting.
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            client
+                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Mint failed");
+            let transfer_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.transfer(proxy_address.clone(), Id::U64(1), vec![]));
+            client
+                .call(&alice, transfer_message, 0, None)
+                .await
+                .expect("Transfer failed");
+
+            // Mint token 2.
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            client
+                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Mint failed");
+
+            // The new token goes to the caller with an asset, the old one stays with the proxy.
+            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.owner_of(Id::U64(2)));
+            let read_owner_of_result = client
+                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(read_owner_of_result, Some(alice_account_id));
+
+            let read_total_assets_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.total_token_assets(Id::U64(2)));
+            let read_total_assets_result = client
+                .call_dry_run(&alice, &read_total_assets_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(read_total_assets_result.0, 1);
+
+            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.owner_of(Id::U64(1)));
+            let read_owner_of_result = client
+                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(read_owner_of_result, Some(proxy_address));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn mint_resolves_token_id_with_tokens_minted_by_others(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
+            let bob = ink_e2e::bob();
+            let alice_account_id = AccountId::try_from(alice.account_id().as_ref()).unwrap();
+            let bob_account_id = AccountId::try_from(bob.account_id().as_ref()).unwrap();
+            let (rmrk_address, proxy_address) = deploy_contracts(&mut client).await;
+
+            // Token 1 is minted through the proxy, token 2 directly on the RMRK contract.
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            client
+                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Mint failed");
+            let direct_mint_message =
+                build_message::<RmrkRef>(rmrk_address.clone()).call(|rmrk| rmrk.mint());
+            client
+                .call(&bob, direct_mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Direct mint failed");
+
+            // Token 3 goes to the caller with an asset.
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            client
+                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Mint failed");
+
+            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.owner_of(Id::U64(3)));
+            let read_owner_of_result = client
+                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(read_owner_of_result, Some(alice_account_id));
 
-            // TODO check why the call is failing silently when no or invalid transferred value is provided.
-            let mint_result = build_call::<DefaultEnvironment>()
-                .call(self.proxy.rmrk_contract.unwrap())
-                .gas_limit(GAS_LIMIT)
-                .transferred_value(transferred_value)
-                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
-                    "MintingLazy::mint"
-                ))))
-                .returns::<()>()
-                .try_invoke();
-            ink::env::debug_println!("mint_result: {:?}", mint_result);
-            mint_result
-                .map_err(|_| ProxyError::MintingError)?
-                .map_err(|_| ProxyError::MintingError)?;
-
-            let token_id = build_call::<DefaultEnvironment>()
-                .call(self.proxy.rmrk_contract.unwrap())
-                .gas_limit(GAS_LIMIT)
-                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
-                    "PSP34::total_supply"
-                ))))
-                .returns::<u64>()
-                .try_invoke()
-                .unwrap()
+            let read_total_assets_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.total_token_assets(Id::U64(3)));
+            let read_total_assets_result = client
+                .call_dry_run(&alice, &read_total_assets_message, 0, None)
+                .await
+                .return_value()
                 .unwrap();
+            assert_eq!(read_total_assets_result.0, 1);
 
-            let asset_id = self.get_pseudo_random((total_assets.unwrap() - 1) as u8) + 1;
-            let add_asset_result = build_call::<DefaultEnvironment>()
-                .call(self.proxy.rmrk_contract.unwrap())
-                .gas_limit(GAS_LIMIT)
-                .exec_input(
-                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
-                        "MultiAsset::add_asset_to_token"
-                    )))
-                    .push_arg(Id::U64(token_id)) // TODO check if there is other way to determine token Id, beside reading totalSupply?
-                    .push_arg(asset_id as u32)
-                    .push_arg(None::<u32>),
-                )
-                .returns::<()>()
-                .try_invoke()
-                .map_err(|_| ProxyError::AddTokenAssetError)?;
-            add_asset_result.map_err(|_| ProxyError::AddTokenAssetError)?;
+            // The directly minted token stays with its minter, without an asset.
+            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.owner_of(Id::U64(2)));
+            let read_owner_of_result = client
+                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(read_owner_of_result, Some(bob_account_id));
 
-            let caller = Self::env().caller();
-            let transfer_token_result = build_call::<DefaultEnvironment>()
-                .call(self.proxy.rmrk_contract.unwrap())
-                .gas_limit(GAS_LIMIT)
-                .exec_input(
-                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP34::transfer")))
-                        .push_arg(caller)
-                        .push_arg(Id::U64(token_id))
-                        .push_arg(Vec::<u8>::new()),
-                )
-                .returns::<()>()
-                .try_invoke()
-                .map_err(|_| ProxyError::OwnershipTransferError)?;
-            transfer_token_result.map_err(|_| ProxyError::OwnershipTransferError)?;
+            let read_total_assets_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.total_token_assets(Id::U64(2)));
+            let read_total_assets_result = client
+                .call_dry_run(&alice, &read_total_assets_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(read_total_assets_result.0, 0);
 
             Ok(())
         }
 
-        #[ink(message)]
-        pub fn rmrk_contract_address(&self) -> AccountId {
-            self.proxy.rmrk_contract.unwrap()
-        }
+        #[ink_e2e::test]
+        async fn mint_resolves_token_id_after_burn(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
+            let alice_account_id = AccountId::try_from(alice.account_id().as_ref()).unwrap();
+            let (rmrk_address, proxy_address) = deploy_contracts(&mut client).await;
 
-        #[ink(message)]
-        pub fn catalog_contract_address(&self) -> AccountId {
-            self.proxy.catalog_contract.unwrap()
-        }
+            // Mint token 1 and burn it, so the total supply drops below the last token id.
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            client
+                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Mint failed");
+            let burn_message =
+                build_message::<RmrkRef>(rmrk_address.clone()).call(|rmrk| rmrk.burn(Id::U64(1)));
+            client
+                .call(&alice, burn_message, 0, None)
+                .await
+                .expect("Burn failed");
 
-        #[ink(message)]
-        pub fn mint_price(&self) -> Balance {
-            self.proxy.mint_price
-        }
+            // Mint token 2.
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            client
+                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
+                .await
+                .expect("Mint failed");
 
-        #[ink(message)]
-        #[modifiers(only_owner)]
-        pub fn set_rmrk_contract_address(&mut self, new_contract_address: AccountId) -> Result<()> {
-            self.proxy.rmrk_contract = Option::Some(new_contract_address);
-            Ok(())
-        }
+            // The new token goes to the caller with an asset, the burnt one is gone.
+            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.owner_of(Id::U64(2)));
+            let read_owner_of_result = client
+                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(read_owner_of_result, Some(alice_account_id));
 
-        #[ink(message)]
-        #[modifiers(only_owner)]
-        pub fn set_catalog_contract_address(
-            &mut self,
-            new_contract_address: AccountId,
-        ) -> Result<()> {
-            self.proxy.catalog_contract = Option::Some(new_contract_address);
-            Ok(())
-        }
+            let read_total_assets_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.total_token_assets(Id::U64(2)));
+            let read_total_assets_result = client
+                .call_dry_run(&alice, &read_total_assets_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(read_total_assets_result.0, 1);
+
+            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
+                .call(|rmrk| rmrk.owner_of(Id::U64(1)));
+            let read_owner_of_result = client
+                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(read_owner_of_result, None);
 
-        #[ink(message)]
-        #[modifiers(only_owner)]
-        pub fn set_mint_price(&mut self, new_mint_price: Balance) -> Result<()> {
-            self.proxy.mint_price = new_mint_price;
             Ok(())
         }
 
-        /// Generates pseudo random number, Used to pick a random asset for a token.
-        fn get_pseudo_random(&mut self, max_value: u8) -> u8 {
-            let seed = self.env().block_timestamp();
-            let mut input: Vec<u8> = Vec::new();
-            input.extend_from_slice(&seed.to_be_bytes());
-            input.extend_from_slice(&self.proxy.salt.to_be_bytes());
-            let mut output = <hash::Keccak256 as hash::HashOutput>::Type::default();
-            ink::env::hash_bytes::<hash::Keccak256>(&input, &mut output);
-            self.proxy.salt += 1;
-            output[0] % (max_value + 1)
-        }
-    }
+        #[ink_e2e::test]
+        async fn add_pool_asset_fails_with_unknown_asset(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
+            let (_, proxy_address) = deploy_contracts(&mut client).await;
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use ink::env::test;
+            let add_pool_asset_message = build_message::<RmrkProxyRef>(proxy_address.clone())
+                .call(|proxy| proxy.add_pool_asset(0, 2));
+            let add_pool_asset_result = client
+                .call_dry_run(&alice, &add_pool_asset_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(add_pool_asset_result, Err(ProxyError::UnknownAsset));
 
-        #[ink::test]
-        fn constructor_works() {
-            let contract = init_contract();
-            assert_eq!(contract.rmrk_contract_address(), rmrk_address());
-            assert_eq!(contract.catalog_contract_address(), catalog_address());
-            assert_eq!(contract.mint_price(), 1_000_000_000_000_000_000);
+            Ok(())
         }
 
-        #[ink::test]
-        fn set_rmrk_contract_address_works() {
-            let mut contract = init_contract();
-            let new_rmrk: AccountId = [0x43; 32].into();
-            assert!(contract.set_rmrk_contract_address(new_rmrk).is_ok());
-            assert_eq!(contract.rmrk_contract_address(), new_rmrk);
-        }
+        #[ink_e2e::test]
+        async fn add_pool_asset_fails_if_rmrk_contract_is_not_a_contract(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
+            let bob_account_id = AccountId::try_from(ink_e2e::bob().account_id().as_ref()).unwrap();
+            let proxy_constructor =
+                RmrkProxyRef::new(bob_account_id, bob_account_id, 1_000_000_000_000_000_000);
+            let proxy_address = client
+                .instantiate("rmrk_proxy", &alice, proxy_constructor, 0, None)
+                .await
+                .expect("Proxy contract instantiation failed")
+                .account_id;
 
-        #[ink::test]
-        fn set_rmrk_contract_address_fails_if_not_owner() {
-            let mut contract = init_contract();
-            let new_rmrk: AccountId = [0x43; 32].into();
-            set_sender(default_accounts().bob);
+            let add_pool_asset_message = build_message::<RmrkProxyRef>(proxy_address.clone())
+                .call(|proxy| proxy.add_pool_asset(0, 1));
+            let add_pool_asset_result = client
+                .call_dry_run(&alice, &add_pool_asset_message, 0, None)
+                .await
+                .return_value();
             assert_eq!(
-                contract.set_rmrk_contract_address(new_rmrk),
-                Err(ProxyError::OwnableError(OwnableError::CallerIsNotOwner))
+                add_pool_asset_result,
+                Err(ProxyError::CrossContractCallFailed {
+                    selector: ink::selector_bytes!("MultiAsset::get_asset_uri")
+                })
             );
-        }
-
-        #[ink::test]
-        fn set_catalog_contract_address_works() {
-            let mut contract = init_contract();
-            let new_rmrk: AccountId = [0x43; 32].into();
-            assert!(contract.set_catalog_contract_address(new_rmrk).is_ok());
-            assert_eq!(contract.catalog_contract_address(), new_rmrk);
-        }
 
-        #[ink::test]
-        fn set_catalog_contract_address_fails_if_not_owner() {
-            let mut contract = init_contract();
-            let new_rmrk: AccountId = [0x43; 32].into();
-            set_sender(default_accounts().bob);
-            assert_eq!(
-                contract.set_catalog_contract_address(new_rmrk),
-                Err(ProxyError::OwnableError(OwnableError::CallerIsNotOwner))
-            );
+            Ok(())
         }
 
-        #[ink::test]
-        fn set_mint_price_works() {
-            let mut contract = init_contract();
-            assert!(contract.set_mint_price(100).is_ok());
-            assert_eq!(contract.mint_price(), 100);
-        }
+        #[ink_e2e::test]
+        async fn add_pool_asset_fails_if_rmrk_contract_has_no_such_message(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
 
-        #[ink::test]
-        fn set_mint_price_fails_if_not_owner() {
-            let mut contract = init_contract();
-            set_sender(default_accounts().bob);
-            assert_eq!(
-                contract.set_mint_price(100),
-                Err(ProxyError::OwnableError(OwnableError::CallerIsNotOwner))
+            // The catalog contract doesn't implement `MultiAsset::get_asset_uri`.
+            let catalog_constructor = CatalogContractRef::new(String::from("ipfs://").into());
+            let catalog_contract_address = client
+                .instantiate("catalog_example", &alice, catalog_constructor, 0, None)
+                .await
+                .expect("Catalog contract instantiation failed")
+                .account_id;
+            let proxy_constructor = RmrkProxyRef::new(
+                catalog_contract_address,
+                catalog_contract_address,
+                1_000_000_000_000_000_000,
             );
-        }
+            let proxy_address = client
+                .instantiate("rmrk_proxy", &alice, proxy_constructor, 0, None)
+                .await
+                .expect("Proxy contract instantiation failed")
+                .account_id;
 
-        #[ink::test]
-        fn mint_fails_if_no_balance() {
-            let mut contract = init_contract();
-            assert_eq!(contract.mint(), Err(ProxyError::BadMintValue));
-        }
+            let add_pool_asset_message = build_message::<RmrkProxyRef>(proxy_address.clone())
+                .call(|proxy| proxy.add_pool_asset(0, 1));
+            let add_pool_asset_result = client
+                .call_dry_run(&alice, &add_pool_asset_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(add_pool_asset_result, Err(ProxyError::LangError));
 
-        fn init_contract() -> RmrkProxy {
-            set_sender(default_accounts().alice);
-            RmrkProxy::new(rmrk_address(), catalog_address(), 1_000_000_000_000_000_000)
+            Ok(())
         }
 
-        fn rmrk_address() -> AccountId {
-            AccountId::from([0x42; 32])
-        }
+        #[ink_e2e::test]
+        async fn mint_fails_with_rmrk_error(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let alice = ink_e2e::alice();
+            let (_, proxy_address) = deploy_contracts(&mut client).await;
 
-        fn catalog_address() -> AccountId {
-            AccountId::from([0x41; 32])
-        }
+            // The RMRK contract rejects mints below its own mint price.
+            let set_price_message = build_message::<RmrkProxyRef>(proxy_address.clone())
+                .call(|proxy| proxy.set_mint_price(0));
+            client
+                .call(&alice, set_price_message, 0, None)
+                .await
+                .expect("Set mint price failed");
 
-        fn default_accounts() -> test::DefaultAccounts<ink::env::DefaultEnvironment> {
-            test::default_accounts::<Environment>()
-        }
+            let mint_message =
+                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            let mint_result = client
+                .call_dry_run(&alice, &mint_message, 0, None)
+                .await
+                .return_value();
+            assert!(matches!(mint_result, Err(ProxyError::RmrkError(_))));
 
-        fn set_sender(sender: AccountId) {
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(sender);
+            Ok(())
         }
-    }
 
-    #[cfg(all(test, feature = "e2e-tests"))]
-    mod e2e_tests {
-        use crate::proxy::rmrk_proxy::RmrkProxyRef;
-        use catalog_example::catalog_example::CatalogContractRef;
-        use ink::primitives::AccountId;
-        use ink_e2e::build_message;
-        use openbrush::contracts::psp34::{
-            psp34_external::PSP34,
-            Id,
-        };
-        use rmrk::{
-            storage::catalog_external::Catalog,
-            traits::multiasset_external::MultiAsset,
-            types::{
-                Part,
-                PartType,
-            },
-        };
-        use rmrk_equippable_lazy::rmrk_equippable_lazy::RmrkRef;
-
-        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
-
-        #[ink_e2e::test]
-        async fn mint_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        /// Deploys catalog, RMRK and proxy contracts, with one part and one asset entry
+        /// in the asset pool of the default collection.
+        async fn deploy_contracts(
+            client: &mut ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>,
+        ) -> (AccountId, AccountId) {
             let alice = ink_e2e::alice();
 
             // *************** Create catalog contract and add parts ***************
@@ -428,51 +4682,14 @@ mod rmrk_proxy {
                 .expect("Proxy contract instantiation failed")
                 .account_id;
 
-            // Mint token.
-            let mint_message =
-                build_message::<RmrkProxyRef>(proxy_address.clone()).call(|proxy| proxy.mint());
+            let add_pool_asset_message = build_message::<RmrkProxyRef>(proxy_address.clone())
+                .call(|proxy| proxy.add_pool_asset(0, 1));
             client
-                .call(&alice, mint_message, 1_000_000_000_000_000_000, None)
-                .await
-                .expect("Mint failed");
-
-            // Check if token was minted
-            let read_total_supply_message =
-                build_message::<RmrkRef>(rmrk_address.clone()).call(|rmrk| rmrk.total_supply());
-
-            let read_total_supply_result = client
-                .call_dry_run(&alice, &read_total_supply_message, 0, None)
-                .await
-                .return_value();
-            assert_eq!(read_total_supply_result, 1);
-
-            // Check if asset has been added to the token.
-            let read_total_assets_message = build_message::<RmrkRef>(rmrk_address.clone())
-                .call(|rmrk| rmrk.total_token_assets(Id::U64(1)));
-
-            let read_total_assets_result = client
-                .call_dry_run(&alice, &read_total_assets_message, 0, None)
-                .await
-                .return_value()
-                .unwrap();
-            // ink::env::debug_println!("token assets: {:?}", read_total_assets_result);
-            assert_eq!(read_total_assets_result.0, 1);
-
-            // Check if token owner is same as the caller.
-            let read_owner_of_message = build_message::<RmrkRef>(rmrk_address.clone())
-                .call(|rmrk| rmrk.owner_of(Id::U64(1)));
-
-            let read_owner_of_result = client
-                .call_dry_run(&alice, &read_owner_of_message, 0, None)
+                .call(&alice, add_pool_asset_message, 0, None)
                 .await
-                .return_value()
-                .unwrap();
-
-            let alice_account_id_32 = alice.account_id();
-            let alice_account_id = AccountId::try_from(alice_account_id_32.as_ref()).unwrap();
-            assert_eq!(read_owner_of_result, alice_account_id);
+                .expect("Add pool asset failed");
 
-            Ok(())
+            (rmrk_address, proxy_address)
         }
     }
 }
@@ -0,0 +1,73 @@
+use crate::{
+    ProxyError,
+    Result,
+};
+use ink::env::chain_extension::{
+    ChainExtensionMethod,
+    FromStatusCode,
+};
+use openbrush::traits::BlockNumber;
+
+/// Id of the chain extension function returning on-chain randomness for a subject,
+/// e.g. backed by `pallet-insecure-randomness-collective-flip`.
+pub const FETCH_RANDOM_EXTENSION_ID: u32 = 1101;
+
+/// Id of the chain extension function returning the hash of a recent block,
+/// e.g. backed by `frame_system::Pallet::block_hash`.
+pub const FETCH_BLOCK_HASH_EXTENSION_ID: u32 = 1102;
+
+/// Source of the seed used to pick a random asset.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum RandomnessSource {
+    /// Keccak256 of the block timestamp and the proxy salt.
+    #[default]
+    PseudoRandom,
+    /// Randomness and block hashes provided by the runtime through the chain extension.
+    ChainExtension,
+}
+
+/// Error code returned by the randomness chain extension.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum RandomReadErr {
+    FailGetRandomSource,
+}
+
+impl FromStatusCode for RandomReadErr {
+    fn from_status_code(status_code: u32) -> core::result::Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            _ => Err(Self::FailGetRandomSource),
+        }
+    }
+}
+
+impl From<RandomReadErr> for ProxyError {
+    fn from(_: RandomReadErr) -> Self {
+        ProxyError::RandomnessUnavailable
+    }
+}
+
+/// Reads on-chain randomness for `subject` through the chain extension.
+pub fn fetch_random(subject: [u8; 32]) -> Result<[u8; 32]> {
+    let random = ChainExtensionMethod::build(FETCH_RANDOM_EXTENSION_ID)
+        .input::<[u8; 32]>()
+        .output::<[u8; 32], false>()
+        .handle_error_code::<RandomReadErr>()
+        .call(&subject)?;
+    Ok(random)
+}
+
+/// Reads the hash of a recent block through the chain extension.
+pub fn fetch_block_hash(block_number: BlockNumber) -> Result<[u8; 32]> {
+    let block_hash = ChainExtensionMethod::build(FETCH_BLOCK_HASH_EXTENSION_ID)
+        .input::<BlockNumber>()
+        .output::<[u8; 32], false>()
+        .handle_error_code::<RandomReadErr>()
+        .call(&block_number)?;
+    Ok(block_hash)
+}
@@ -1,23 +1,249 @@
+use crate::RandomnessSource;
+use ink::{
+    prelude::vec::Vec,
+    storage::Lazy,
+};
 use openbrush::{
     contracts::{
+        access_control::{
+            AccessControlError,
+            RoleType,
+        },
         ownable::OwnableError,
+        pausable::PausableError,
         reentrancy_guard::ReentrancyGuardError,
     },
+    storage::Mapping,
     traits::{
         AccountId,
         Balance,
+        BlockNumber,
+        Timestamp,
     },
 };
+use rmrk::{
+    errors::Error as RmrkError,
+    types::AssetId,
+};
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 
+/// Can change mint prices, pricing strategy, sale phases and accepted PSP22 tokens.
+pub const PRICE_ADMIN: RoleType = ink::selector_id!("PRICE_ADMIN");
+/// Can change contract addresses, limits, assets, randomness and allowlist settings.
+pub const CONFIG_ADMIN: RoleType = ink::selector_id!("CONFIG_ADMIN");
+/// Can pause and unpause minting.
+pub const PAUSER: RoleType = ink::selector_id!("PAUSER");
+/// Can set payees and withdraw PSP22 payments.
+pub const TREASURER: RoleType = ink::selector_id!("TREASURER");
+/// Can airdrop tokens.
+pub const MINTER: RoleType = ink::selector_id!("MINTER");
+
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
-    pub rmrk_contract: Option<AccountId>,
-    pub catalog_contract: Option<AccountId>,
-    pub mint_price: Balance,
+    // Contract addresses and mint price of storage version 1, moved to collection `0` by
+    // `migrate`. Kept in place so that the fields after them decode.
+    pub legacy_rmrk_contract: Option<AccountId>,
+    pub legacy_catalog_contract: Option<AccountId>,
+    pub legacy_mint_price: Balance,
     pub salt: u64, // used for pseudo random number generation
+    pub max_mints_per_call: u32,
+    pub asset_weights: Vec<(AssetId, u32)>,
+    pub mint_commitments: Mapping<AccountId, MintCommitment>,
+    pub reveal_window: BlockNumber,
+    pub randomness_source: RandomnessSource,
+    pub allowlist_root: Option<[u8; 32]>,
+    pub allowlist_claimed: Mapping<AccountId, u32>,
+    pub voucher_signer: Option<[u8; 33]>,
+    pub used_voucher_nonces: Mapping<u64, bool>,
+    pub sale_phases: Vec<SalePhase>,
+    pub phase_minted: Mapping<Timestamp, u32>,
+    pub phase_wallet_minted: Mapping<(Timestamp, AccountId), u32>,
+    pub wallet_minted: Mapping<AccountId, u32>,
+    pub max_mints_per_wallet: u32,
+    pub payees: Vec<(AccountId, u16)>,
+    pub revenue: Balance,
+    pub released: Mapping<AccountId, Balance>,
+    pub refund_overpayment: bool,
+    pub pricing_strategy: PricingStrategy,
+    pub total_minted: u64,
+    pub psp22_prices: Mapping<AccountId, Balance>,
+    pub gas_limits: GasLimits,
+    pub version: u32,
+    // Fields added after storage version 1 are kept out of the packed layout, so that
+    // storage written by version 1 still decodes.
+    pub proposal: Lazy<Option<Proposal>>,
+    pub timelock_delay: Lazy<Timestamp>,
+    pub pending_owner: Lazy<Option<AccountId>>,
+    pub collections: Mapping<CollectionId, CollectionConfig>,
+    pub next_collection_id: Lazy<CollectionId>,
+    pub commitment_penalty: Lazy<u16>,
+}
+
+pub type CollectionId = u32;
+
+/// RMRK collection minted through the proxy.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct CollectionConfig {
+    pub rmrk_contract: AccountId,
+    pub catalog_contract: AccountId,
+    /// Price of a single token. Unless payees are set, the price is forwarded to the RMRK
+    /// contract, so it has to equal the RMRK contract's price per mint.
+    pub mint_price: Balance,
+    /// Assets picked on mint. Nothing can be minted while the pool is empty.
+    pub asset_pool: Vec<AssetId>,
+    /// Whether minting of the collection is paused.
+    pub paused: bool,
+}
+
+/// Mint paid for with `commit_mint`, waiting to be revealed.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct MintCommitment {
+    /// Keccak256 hash of the committer's account id followed by the secret.
+    pub hash: [u8; 32],
+    /// Block in which the commitment was made.
+    pub block_number: BlockNumber,
+    /// Value paid for the mint.
+    pub deposit: Balance,
+    /// Start of the sale phase the mint was counted in, if phases are set.
+    pub phase_start: Option<Timestamp>,
+}
+
+/// Time window with its own mint price and limits.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct SalePhase {
+    /// Timestamp at which the phase starts, inclusive.
+    pub start: Timestamp,
+    /// Timestamp at which the phase ends, exclusive.
+    pub end: Timestamp,
+    /// Price of a single token.
+    pub price: Balance,
+    /// Maximum number of tokens a single account can mint in the phase.
+    pub wallet_limit: Option<u32>,
+    /// Whether only `allowlist_mint` can be used in the phase.
+    pub allowlist_only: bool,
+    /// Maximum number of tokens minted in the phase.
+    pub supply_cap: Option<u32>,
+}
+
+/// Way the mint price is calculated.
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum PricingStrategy {
+    /// Price of the active sale phase or `mint_price`.
+    #[default]
+    Fixed,
+    /// Price falling over time down to a floor.
+    DutchAuction(DutchAuction),
+    /// Price growing by `price_increment` with every token minted through the proxy.
+    Linear {
+        base_price: Balance,
+        price_increment: Balance,
+    },
+    /// Price steps as `(minted, price)` pairs, ordered by the number of minted tokens.
+    /// The price applies once the proxy has minted at least `minted` tokens.
+    Stepwise(Vec<(u64, Balance)>),
+}
+
+/// Gas limits of cross contract calls made by the proxy. Zero forwards all remaining gas.
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct GasLimits {
+    /// Checking asset entries and reading the id of the minted token.
+    pub query: u64,
+    /// Minting a token.
+    pub mint: u64,
+    /// Adding an asset to the minted token.
+    pub add_asset_to_token: u64,
+    /// Transferring the minted token and PSP22 payments.
+    pub transfer: u64,
+}
+
+/// Dutch auction price schedule.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct DutchAuction {
+    /// Timestamp at which the price starts to fall.
+    pub start_time: Timestamp,
+    /// Price before the first decay.
+    pub start_price: Balance,
+    /// Price never falls below this value.
+    pub floor_price: Balance,
+    /// Amount the price falls by every interval.
+    pub price_decay: Balance,
+    /// Time between two price decays, in milliseconds.
+    pub decay_interval: Timestamp,
+}
+
+impl DutchAuction {
+    /// Returns the auction price at given timestamp.
+    pub fn price_at(&self, now: Timestamp) -> Balance {
+        let intervals = now.saturating_sub(self.start_time) / self.decay_interval;
+        let decay = self.price_decay.saturating_mul(intervals as Balance);
+        self.start_price.saturating_sub(decay).max(self.floor_price)
+    }
+}
+
+/// Change of a critical setting, applied only after the timelock delay.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ProposedChange {
+    RmrkContract(AccountId),
+    CatalogContract(AccountId),
+    TimelockDelay(Timestamp),
+    /// Code hash the contract is upgraded to.
+    CodeHash([u8; 32]),
+}
+
+/// Proposed change waiting for its timelock delay to pass.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Proposal {
+    pub change: ProposedChange,
+    /// Timestamp from which the change can be executed.
+    pub executable_at: Timestamp,
+}
+
+/// Permission to mint a token, signed off-chain by the voucher signer.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct MintVoucher {
+    /// Account receiving the minted token.
+    pub recipient: AccountId,
+    /// Value which has to be transferred with the mint.
+    pub price: Balance,
+    /// Unique voucher number, used to prevent replays.
+    pub nonce: u64,
+    /// Timestamp after which the voucher can't be used.
+    pub expiry: Timestamp,
 }
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -25,15 +251,123 @@ pub struct Data {
 pub enum ProxyError {
     /// A caller is not a marketplace owner.
     OwnableError(OwnableError),
+    /// A caller doesn't have the role required by the message.
+    AccessControlError(AccessControlError),
     /// A caller is trying to make second call while 1st one is still executing.
     ReentrancyError(ReentrancyGuardError),
-    /// Something went wrong while invoking mint method on the proxied contract.
+    /// Minting is paused, or unpausing while not paused.
+    PausableError(PausableError),
+    /// The proxy doesn't own any token after minting.
     MintingError,
-    OwnershipTransferError,
-    AddTokenAssetError,
     NoAssetsDefined,
-    TooManyAssetsDefined,
     BadMintValue,
+    /// Number of tokens to mint is zero or above the per call limit.
+    BadMintCount,
+    /// Asset weights are empty, duplicated or their sum overflows.
+    InvalidAssetWeights,
+    /// The caller already has a mint commitment waiting to be revealed.
+    CommitmentExists,
+    /// The caller has no mint commitment.
+    NoCommitment,
+    /// A commitment can be revealed only once the block after the commit block is sealed.
+    RevealTooEarly,
+    /// The reveal window has passed, the commitment can only be refunded.
+    CommitmentExpired,
+    /// The commitment is still revealable and can't be refunded.
+    CommitmentNotExpired,
+    /// The revealed secret doesn't match the commitment hash.
+    InvalidSecret,
+    /// The reveal window is shorter than two blocks, which leaves no block to reveal in.
+    InvalidRevealWindow,
+    /// The commitment penalty is above 10000 basis points.
+    InvalidCommitmentPenalty,
+    /// Native token transfer from the proxy failed.
+    TransferFailed,
+    /// The randomness chain extension failed to provide a random seed or a block hash.
+    RandomnessUnavailable,
+    /// Commit-reveal minting requires `RandomnessSource::ChainExtension`.
+    ChainRandomnessRequired,
+    /// Allowlist Merkle root hasn't been set.
+    AllowlistNotSet,
+    /// The Merkle proof doesn't match the caller and allocation.
+    NotAllowlisted,
+    /// The caller has already minted the whole allowlist allocation.
+    AllowlistLimitReached,
+    /// Voucher signer public key hasn't been set.
+    VoucherSignerNotSet,
+    /// The voucher isn't signed by the voucher signer.
+    InvalidVoucherSignature,
+    /// The voucher expiry has passed.
+    VoucherExpired,
+    /// The voucher nonce has already been used.
+    VoucherAlreadyUsed,
+    /// Sale phases are not ordered or they overlap.
+    InvalidSalePhases,
+    /// The first sale phase hasn't started yet.
+    SaleNotStarted,
+    /// The last sale phase has ended.
+    SaleEnded,
+    /// Current time is between sale phases.
+    NoActiveSalePhase,
+    /// The active sale phase is open only for allowlisted accounts.
+    AllowlistRequired,
+    /// The recipient has reached the mint limit of the active sale phase.
+    PhaseWalletLimitReached,
+    /// All tokens of the active sale phase have been minted.
+    PhaseSupplyReached,
+    /// The recipient has reached the maximum number of mints per wallet.
+    WalletLimitReached,
+    /// Payee shares don't add up to 10000 basis points, are zero or duplicated.
+    InvalidPayees,
+    /// The account is not a payee.
+    NotPayee,
+    /// The payee has no revenue to release.
+    NothingToRelease,
+    /// Payees can't be changed until all revenue is released.
+    UnreleasedRevenue,
+    /// Pricing strategy parameters are not valid.
+    InvalidPricingStrategy,
+    /// The PSP22 token is not accepted as payment.
+    Psp22NotAccepted,
+    /// Transfer of PSP22 tokens failed.
+    Psp22TransferFailed,
+    /// RMRK or catalog contract address is not set.
+    NotConfigured,
+    /// Cross contract call with the selector couldn't be executed.
+    CrossContractCallFailed {
+        selector: [u8; 4],
+    },
+    /// The called contract couldn't dispatch the message.
+    LangError,
+    /// The RMRK contract returned an error.
+    RmrkError(RmrkError),
+    /// The contract code couldn't be replaced.
+    UpgradeFailed,
+    /// Storage is already at the current version.
+    NothingToMigrate,
+    /// There is no pending proposal.
+    NoProposal,
+    /// Another proposal is pending, it has to be executed or cancelled first.
+    ProposalPending,
+    /// Timelock delay of the proposal hasn't passed yet.
+    ProposalNotReady,
+    /// The caller is not the proposed owner.
+    NotPendingOwner,
+    /// No collection is registered with the id.
+    UnknownCollection,
+    /// Minting of the collection is paused.
+    CollectionPaused,
+    /// The collection can't be registered or retired.
+    InvalidCollection,
+    /// Prices different from the mint price require payees, since without them the price
+    /// is forwarded to the RMRK contract, which accepts only its own price per mint.
+    PriceRequiresPayees,
+    /// The asset is not defined in the RMRK contract.
+    UnknownAsset,
+    /// The asset is already in the asset pool.
+    AssetAlreadyInPool,
+    /// The asset is not in the asset pool.
+    AssetNotInPool,
 }
 
 pub type Result<T> = core::result::Result<T, ProxyError>;
@@ -44,8 +378,26 @@ impl From<OwnableError> for ProxyError {
     }
 }
 
+impl From<AccessControlError> for ProxyError {
+    fn from(error: AccessControlError) -> Self {
+        ProxyError::AccessControlError(error)
+    }
+}
+
 impl From<ReentrancyGuardError> for ProxyError {
     fn from(error: ReentrancyGuardError) -> Self {
         ProxyError::ReentrancyError(error)
     }
 }
+
+impl From<PausableError> for ProxyError {
+    fn from(error: PausableError) -> Self {
+        ProxyError::PausableError(error)
+    }
+}
+
+impl From<RmrkError> for ProxyError {
+    fn from(error: RmrkError) -> Self {
+        ProxyError::RmrkError(error)
+    }
+}